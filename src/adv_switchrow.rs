@@ -1,8 +1,11 @@
 use std::cell::{Cell, RefCell};
+use std::sync::OnceLock;
 
 use adw::subclass::prelude::*;
 use adw::prelude::*;
 use gtk::glib;
+use glib::clone;
+use glib::subclass::Signal;
 
 //------------------------------------------------------------------------------
 // MODULE: AdvSwitchRow
@@ -17,11 +20,19 @@ mod imp {
     #[properties(wrapper_type = super::AdvSwitchRow)]
     #[template(resource = "/com/github/Syncer/ui/adv_switchrow.ui")]
     pub struct AdvSwitchRow {
+        #[template_child]
+        pub(super) inherited_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) revert_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub(super) switch: TemplateChild<gtk::Switch>,
 
         #[property(get, set)]
         active: Cell<bool>,
+        #[property(get, set)]
+        inherited: Cell<bool>,
+        #[property(get, set)]
+        overridden: Cell<bool>,
 
         #[property(get, set)]
         prop_name: RefCell<String>,
@@ -47,6 +58,19 @@ mod imp {
 
     #[glib::derived_properties]
     impl ObjectImpl for AdvSwitchRow {
+        //---------------------------------------
+        // Signals
+        //---------------------------------------
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("revert")
+                        .build(),
+                ]
+            })
+        }
+
         //---------------------------------------
         // Constructor
         //---------------------------------------
@@ -55,6 +79,7 @@ mod imp {
 
             let obj = self.obj();
 
+            obj.setup_signals();
             obj.setup_widgets();
         }
     }
@@ -75,6 +100,21 @@ glib::wrapper! {
 }
 
 impl AdvSwitchRow {
+    //---------------------------------------
+    // Setup signals
+    //---------------------------------------
+    fn setup_signals(&self) {
+        let imp = self.imp();
+
+        // Revert button clicked signal
+        imp.revert_button.connect_clicked(clone!(
+            #[weak(rename_to = row)] self,
+            move |_| {
+                row.emit_by_name::<()>("revert", &[]);
+            }
+        ));
+    }
+
     //---------------------------------------
     // Setup widgets
     //---------------------------------------
@@ -85,5 +125,14 @@ impl AdvSwitchRow {
             .bidirectional()
             .sync_create()
             .build();
+
+        // Bind inheritance properties to widgets
+        self.bind_property("inherited", &imp.inherited_label.get(), "visible")
+            .sync_create()
+            .build();
+
+        self.bind_property("overridden", &imp.revert_button.get(), "visible")
+            .sync_create()
+            .build();
     }
 }
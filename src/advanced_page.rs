@@ -1,13 +1,16 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 
 use adw::subclass::prelude::*;
 use adw::prelude::*;
-use gtk::glib;
+use gtk::{gio, glib};
+use glib::{clone, closure_local};
 
 use crate::{
     profile_object::ProfileObject,
     filter_expander_row::FilterExpanderRow,
-    adv_switchrow::AdvSwitchRow
+    filter_test::{run_filter_test, FilterTestEntry},
+    adv_switchrow::AdvSwitchRow,
+    rsync_version::RsyncCapabilities
 };
 
 //------------------------------------------------------------------------------
@@ -23,15 +26,47 @@ mod imp {
     #[properties(wrapper_type = super::AdvancedPage)]
     #[template(resource = "/com/github/Syncer/ui/advanced_page.ui")]
     pub struct AdvancedPage {
+        #[template_child]
+        pub(super) template_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) template_model: TemplateChild<gtk::StringList>,
         #[template_child]
         pub(super) filter_expander_row: TemplateChild<FilterExpanderRow>,
         #[template_child]
         pub(super) switches_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub(super) success_codes_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(super) warning_codes_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(super) retry_attempts_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(super) retry_delay_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(super) retry_codes_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(super) io_timeout_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(super) connect_timeout_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(super) stall_timeout_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(super) stall_terminate_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(super) rsync_path_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(super) rsync_version_row: TemplateChild<adw::ActionRow>,
 
         #[property(get, set, nullable)]
         profile: RefCell<Option<ProfileObject>>,
+        #[property(get, set, nullable)]
+        profile_model: RefCell<Option<gio::ListStore>>,
+        #[property(get, set, nullable)]
+        filter_set_model: RefCell<Option<gio::ListStore>>,
 
         pub(super) bindings: RefCell<Option<Vec<glib::Binding>>>,
+        pub(super) inheritance_handler: RefCell<Option<(ProfileObject, glib::SignalHandlerId)>>,
+        pub(super) template_change: Cell<bool>,
     }
 
     //---------------------------------------
@@ -47,6 +82,11 @@ mod imp {
             AdvSwitchRow::ensure_type();
 
             klass.bind_template();
+
+            // Filters test action
+            klass.install_action_async("filters.test", None, async |page, _, _| {
+                page.filter_test_dialog().await;
+            });
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -65,11 +105,19 @@ mod imp {
             let obj = self.obj();
 
             obj.setup_signals();
+            obj.setup_widgets();
         }
     }
 
     impl WidgetImpl for AdvancedPage {}
-    impl NavigationPageImpl for AdvancedPage {}
+    impl NavigationPageImpl for AdvancedPage {
+        //---------------------------------------
+        // Showing function
+        //---------------------------------------
+        fn showing(&self) {
+            self.obj().update_template_combo();
+        }
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -107,10 +155,122 @@ impl AdvancedPage {
         switches
     }
 
+    //---------------------------------------
+    // Set capabilities function
+    //---------------------------------------
+    // Disables options not supported by the installed rsync
+    pub fn set_capabilities(&self, capabilities: Option<&RsyncCapabilities>) {
+        let imp = self.imp();
+
+        let unsupported_reason = |arg: &str| capabilities.and_then(|capabilities| capabilities.unsupported_reason(arg));
+
+        let mut unsupported: Vec<&str> = vec![];
+
+        // Switch rows
+        for switch in self.switches() {
+            let Some(arg) = ProfileObject::option_switch(&switch.prop_name()) else {
+                continue;
+            };
+
+            let reason = unsupported_reason(arg);
+
+            if reason.is_some() {
+                unsupported.push(arg);
+            }
+
+            switch.set_sensitive(reason.is_none());
+            switch.set_tooltip_text(reason.as_deref());
+        }
+
+        // Timeout rows
+        for (row, arg) in [(&*imp.io_timeout_row, "--timeout"), (&*imp.connect_timeout_row, "--contimeout")] {
+            let reason = unsupported_reason(arg);
+
+            if reason.is_some() {
+                unsupported.push(arg);
+            }
+
+            row.set_sensitive(reason.is_none());
+            row.set_tooltip_text(reason.as_deref());
+        }
+
+        // Filter test requires --debug=filter
+        self.action_set_enabled("filters.test", unsupported_reason("--debug").is_none());
+
+        // Version row
+        let subtitle = match capabilities {
+            Some(capabilities) if unsupported.is_empty() => capabilities.version.to_string(),
+            Some(capabilities) => format!("{} (not supported: {})", capabilities.version, unsupported.join(", ")),
+            None => String::from("Unknown")
+        };
+
+        imp.rsync_version_row.set_subtitle(&subtitle);
+    }
+
+    //---------------------------------------
+    // Update template combo function
+    //---------------------------------------
+    fn update_template_combo(&self) {
+        let imp = self.imp();
+
+        let (Some(profile), Some(profile_model)) = (self.profile(), self.profile_model()) else {
+            return;
+        };
+
+        // Get profiles that can be used as template (avoid inheritance cycles)
+        let names: Vec<String> = profile_model.iter::<ProfileObject>()
+            .flatten()
+            .filter(|template| template != &profile && !template.inherits_from(&profile))
+            .map(|template| template.name())
+            .collect();
+
+        let parent = profile.parent();
+
+        let selected = names.iter()
+            .position(|name| *name == parent)
+            .map_or(0, |pos| pos as u32 + 1);
+
+        // Populate template combo
+        imp.template_change.set(true);
+
+        let items: Vec<&str> = ["None"].into_iter()
+            .chain(names.iter().map(String::as_str))
+            .collect();
+
+        imp.template_model.splice(0, imp.template_model.n_items(), &items);
+
+        imp.template_combo.set_selected(selected);
+
+        imp.template_change.set(false);
+    }
+
+    //---------------------------------------
+    // Update inheritance function
+    //---------------------------------------
+    fn update_inheritance(&self) {
+        let imp = self.imp();
+
+        let Some(profile) = self.profile() else {
+            return;
+        };
+
+        for switch in self.switches() {
+            let nick = switch.prop_name();
+
+            switch.set_inherited(profile.is_inherited(&nick));
+            switch.set_overridden(profile.is_overridden(&nick));
+        }
+
+        imp.filter_expander_row.set_inherited(profile.is_inherited("filters"));
+        imp.filter_expander_row.set_overridden(profile.is_overridden("filters"));
+    }
+
     //---------------------------------------
     // Setup signals
     //---------------------------------------
     fn setup_signals(&self) {
+        let imp = self.imp();
+
         // Profile property notify signal
         self.connect_profile_notify(|page| {
             let imp = page.imp();
@@ -122,6 +282,11 @@ impl AdvancedPage {
                 }
             }
 
+            // Disconnect inheritance changed handler
+            if let Some((profile, handler)) = imp.inheritance_handler.take() {
+                profile.disconnect(handler);
+            }
+
             if let Some(profile) = page.profile() {
                 // Bind profile property to widgets
                 let mut bindings: Vec<glib::Binding> = page.switches().iter()
@@ -140,6 +305,76 @@ impl AdvancedPage {
                         .build()
                 );
 
+                bindings.push(
+                    profile.bind_property("success-codes", &imp.success_codes_row.get(), "text")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("warning-codes", &imp.warning_codes_row.get(), "text")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("retry-attempts", &imp.retry_attempts_row.get(), "value")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("retry-delay", &imp.retry_delay_row.get(), "value")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("retry-codes", &imp.retry_codes_row.get(), "text")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("io-timeout", &imp.io_timeout_row.get(), "value")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("connect-timeout", &imp.connect_timeout_row.get(), "value")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("stall-timeout", &imp.stall_timeout_row.get(), "value")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("stall-terminate", &imp.stall_terminate_row.get(), "active")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
+                bindings.push(
+                    profile.bind_property("rsync-path", &imp.rsync_path_row.get(), "text")
+                        .bidirectional()
+                        .sync_create()
+                        .build()
+                );
+
                 // Bind profile property to page title
                 bindings.push(
                     profile.bind_property("name", page, "title")
@@ -150,9 +385,241 @@ impl AdvancedPage {
                 // Store bindings
                 imp.bindings.replace(Some(bindings));
 
+                // Profile inheritance changed signal
+                let handler = profile.connect_closure("inheritance-changed", false, closure_local!(
+                    #[weak] page,
+                    move |_: ProfileObject| {
+                        page.update_inheritance();
+                        page.update_template_combo();
+                    }
+                ));
+
+                imp.inheritance_handler.replace(Some((profile, handler)));
+
                 // Contract filter expander row
                 imp.filter_expander_row.set_expanded(false);
             }
+
+            page.update_template_combo();
+            page.update_inheritance();
         });
+
+        // Template combo selected property notify signal
+        imp.template_combo.connect_selected_notify(clone!(
+            #[weak(rename_to = page)] self,
+            move |combo| {
+                let imp = page.imp();
+
+                if imp.template_change.get() {
+                    return;
+                }
+
+                if let Some(profile) = page.profile() {
+                    let parent = combo.selected_item()
+                        .and_downcast::<gtk::StringObject>()
+                        .filter(|_| combo.selected() != 0)
+                        .map(|item| item.string().to_string())
+                        .unwrap_or_default();
+
+                    profile.set_parent(parent);
+                }
+            }
+        ));
+
+        // Switch revert signals
+        for switch in self.switches() {
+            switch.connect_closure("revert", false, closure_local!(
+                #[weak(rename_to = page)] self,
+                move |switch: AdvSwitchRow| {
+                    if let Some(profile) = page.profile() {
+                        profile.revert(&switch.prop_name());
+                    }
+                }
+            ));
+        }
+
+        // Filter expander row revert signal
+        imp.filter_expander_row.connect_closure("revert", false, closure_local!(
+            #[weak(rename_to = page)] self,
+            move |_: FilterExpanderRow| {
+                if let Some(profile) = page.profile() {
+                    profile.revert("filters");
+                }
+            }
+        ));
+    }
+
+    //---------------------------------------
+    // Setup widgets
+    //---------------------------------------
+    fn setup_widgets(&self) {
+        let imp = self.imp();
+
+        // Bind filter set model to filter expander row
+        self.bind_property("filter-set-model", &imp.filter_expander_row.get(), "filter-set-model")
+            .sync_create()
+            .build();
+
+        // Bind profile model to filter expander row (for filter set references)
+        self.bind_property("profile-model", &imp.filter_expander_row.get(), "profile-model")
+            .sync_create()
+            .build();
+    }
+
+    //---------------------------------------
+    // Filter test dialog function
+    //---------------------------------------
+    async fn filter_test_dialog(&self) {
+        let Some(profile) = self.profile() else {
+            return;
+        };
+
+        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/filter_test_dialog.ui");
+
+        let dialog: adw::Dialog = builder.object("dialog")
+            .expect("Could not get object from resource");
+
+        let search_entry: gtk::SearchEntry = builder.object("search_entry")
+            .expect("Could not get object from resource");
+
+        let stack: gtk::Stack = builder.object("stack")
+            .expect("Could not get object from resource");
+
+        let error_page: adw::StatusPage = builder.object("error_page")
+            .expect("Could not get object from resource");
+
+        let list_view: gtk::ListView = builder.object("list_view")
+            .expect("Could not get object from resource");
+
+        let summary_label: gtk::Label = builder.object("summary_label")
+            .expect("Could not get object from resource");
+
+        stack.set_visible_child_name("loading");
+
+        dialog.present(Some(self));
+
+        if profile.source().is_empty() {
+            error_page.set_description(Some("No source folder selected"));
+            stack.set_visible_child_name("error");
+
+            return;
+        }
+
+        // Run rsync and get filter results
+        let entries = match run_filter_test(&profile).await {
+            Ok(entries) => entries,
+            Err(error) => {
+                error_page.set_description(Some(&error));
+                stack.set_visible_child_name("error");
+
+                return;
+            }
+        };
+
+        let excluded = entries.iter()
+            .filter(|entry| !entry.included)
+            .count();
+
+        summary_label.set_label(&format!("{} included, {excluded} excluded", entries.len() - excluded));
+
+        // Create results model
+        let model = gio::ListStore::new::<glib::BoxedAnyObject>();
+
+        model.extend(entries.into_iter().map(glib::BoxedAnyObject::new));
+
+        let filter = gtk::CustomFilter::new(clone!(
+            #[weak] search_entry,
+            #[upgrade_or] true,
+            move |obj| {
+                let text = search_entry.text();
+
+                obj.downcast_ref::<glib::BoxedAnyObject>()
+                    .is_some_and(|obj| obj.borrow::<FilterTestEntry>().path.contains(text.as_str()))
+            }
+        ));
+
+        search_entry.connect_search_changed(clone!(
+            #[weak] filter,
+            move |_| {
+                filter.changed(gtk::FilterChange::Different);
+            }
+        ));
+
+        let filter_model = gtk::FilterListModel::new(Some(model), Some(filter));
+
+        // Create results factory
+        let factory = gtk::SignalListItemFactory::new();
+
+        factory.connect_setup(|_, item| {
+            let path_label = gtk::Label::builder()
+                .xalign(0.0)
+                .ellipsize(gtk::pango::EllipsizeMode::Middle)
+                .build();
+
+            let rule_label = gtk::Label::builder()
+                .xalign(0.0)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .css_classes(["caption", "dimmed"])
+                .build();
+
+            let label_box = gtk::Box::new(gtk::Orientation::Vertical, 3);
+            label_box.append(&path_label);
+            label_box.append(&rule_label);
+
+            let item_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+            item_box.append(&gtk::Image::new());
+            item_box.append(&label_box);
+
+            item.downcast_ref::<gtk::ListItem>()
+                .expect("Could not downcast to 'GtkListItem'")
+                .set_child(Some(&item_box));
+        });
+
+        factory.connect_bind(|_, item| {
+            let item = item.downcast_ref::<gtk::ListItem>()
+                .expect("Could not downcast to 'GtkListItem'");
+
+            let item_box = item.child()
+                .and_downcast::<gtk::Box>()
+                .expect("Could not downcast to 'GtkBox'");
+
+            let image = item_box.first_child()
+                .and_downcast::<gtk::Image>()
+                .expect("Could not downcast to 'GtkImage'");
+
+            let label_box = item_box.last_child()
+                .and_downcast::<gtk::Box>()
+                .expect("Could not downcast to 'GtkBox'");
+
+            let path_label = label_box.first_child()
+                .and_downcast::<gtk::Label>()
+                .expect("Could not downcast to 'GtkLabel'");
+
+            let rule_label = label_box.last_child()
+                .and_downcast::<gtk::Label>()
+                .expect("Could not downcast to 'GtkLabel'");
+
+            let obj = item.item()
+                .and_downcast::<glib::BoxedAnyObject>()
+                .expect("Could not downcast to 'BoxedAnyObject'");
+
+            let entry = obj.borrow::<FilterTestEntry>();
+
+            image.set_icon_name(Some(if entry.is_dir { "stats-dir-symbolic" } else { "stats-file-symbolic" }));
+
+            path_label.set_label(&entry.path);
+            path_label.set_css_classes(if entry.included { &[] } else { &["error"] });
+
+            rule_label.set_label(&match (&entry.rule, entry.included) {
+                (Some(rule), true) => format!("Included by {rule}"),
+                (Some(rule), false) => format!("Excluded by {rule}"),
+                (None, _) => "Included (no matching rule)".to_owned()
+            });
+        });
+
+        list_view.set_factory(Some(&factory));
+        list_view.set_model(Some(&gtk::NoSelection::new(Some(filter_model))));
+
+        stack.set_visible_child_name("results");
     }
 }
@@ -52,6 +52,22 @@ mod imp {
 
             window.present();
         }
+
+        //---------------------------------------
+        // Open handler
+        //---------------------------------------
+        fn open(&self, files: &[gio::File], _: &str) {
+            let application = self.obj();
+
+            // Show main window and import profiles from files
+            let window = application.active_window()
+                .and_downcast::<AppWindow>()
+                .unwrap_or_else(|| AppWindow::new(&application));
+
+            window.present();
+
+            window.import_profiles(files);
+        }
     }
 
     impl GtkApplicationImpl for Application {}
@@ -0,0 +1,279 @@
+use std::io::{self, Write as _};
+use std::fs;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::{json, to_string_pretty, from_str, Map as JsonMap, Value as JsonValue};
+
+use crate::profile_object::ProfileObject;
+use crate::filter_rule::FilterRule;
+use crate::filter_set_object::FilterSetObject;
+
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+pub const CONFIG_VERSION: u64 = 2;
+const BACKUP_COUNT: usize = 5;
+pub const BACKUP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+//------------------------------------------------------------------------------
+// DATA: Migrations
+//------------------------------------------------------------------------------
+// Migration at index N upgrades a config from version N to version N + 1
+type Migration = fn(JsonMap<String, JsonValue>, &mut Vec<String>) -> JsonMap<String, JsonValue>;
+
+const MIGRATIONS: [Migration; CONFIG_VERSION as usize] = [
+    migrate_v0,
+    migrate_v1,
+];
+
+// Version 0: profiles stored at top level without version
+fn migrate_v0(json_object: JsonMap<String, JsonValue>, _: &mut Vec<String>) -> JsonMap<String, JsonValue> {
+    let mut migrated = JsonMap::new();
+
+    migrated.insert("version".to_owned(), json!(1));
+    migrated.insert("profiles".to_owned(), JsonValue::Object(json_object));
+
+    migrated
+}
+
+// Version 1: filters stored as --exclude/--include options
+fn migrate_v1(mut json_object: JsonMap<String, JsonValue>, diagnostics: &mut Vec<String>) -> JsonMap<String, JsonValue> {
+    let filters = json_object.get_mut("profiles")
+        .and_then(JsonValue::as_object_mut)
+        .into_iter()
+        .flat_map(|profiles| profiles.iter_mut())
+        .filter_map(|(name, profile)| Some((name, profile.get_mut("filters")?.as_array_mut()?)))
+        .flat_map(|(name, filters)| filters.iter_mut().map(move |filter| (name, filter)));
+
+    // Unconvertible filters are kept (profile cannot be run until they are fixed)
+    for (name, filter) in filters {
+        match filter.as_str().and_then(FilterRule::parse) {
+            Some(rule) => *filter = json!(rule.to_string()),
+            None => diagnostics.push(format!("Filter {filter} of profile \"{name}\" could not be converted to a filter rule"))
+        }
+    }
+
+    json_object.insert("version".to_owned(), json!(2));
+
+    json_object
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: Config
+//------------------------------------------------------------------------------
+#[derive(Default, Debug)]
+pub struct Config {
+    pub version: u64,
+    pub profiles: Vec<ProfileObject>,
+    pub filter_sets: Vec<FilterSetObject>,
+    pub diagnostics: Vec<String>
+}
+
+impl Config {
+    //---------------------------------------
+    // Parse function
+    //---------------------------------------
+    pub fn parse(json_str: &str) -> io::Result<Self> {
+        let mut json_object: JsonMap<String, JsonValue> = from_str(json_str)?;
+
+        // Get config version (version 0 has no version key)
+        let version = json_object.get("version")
+            .and_then(JsonValue::as_u64)
+            .unwrap_or(0);
+
+        if version > CONFIG_VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                format!("Config version {version} is newer than supported version {CONFIG_VERSION}")));
+        }
+
+        let mut diagnostics = vec![];
+
+        // Apply migrations
+        for migrate in &MIGRATIONS[version as usize..] {
+            json_object = migrate(json_object, &mut diagnostics);
+        }
+
+        // Report unknown keys
+        for key in json_object.keys().filter(|&key| !matches!(key.as_str(), "version" | "profiles" | "filter-sets")) {
+            diagnostics.push(format!("Unknown setting \"{key}\" ignored"));
+        }
+
+        // Get profiles
+        let profiles: Vec<ProfileObject> = json_object.get("profiles")
+            .and_then(JsonValue::as_object)
+            .map(|json_map| {
+                json_map.iter()
+                    .filter_map(|(name, value)| ProfileObject::from_json(name, value, &mut diagnostics))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Get filter sets
+        let filter_sets: Vec<FilterSetObject> = json_object.get("filter-sets")
+            .and_then(JsonValue::as_object)
+            .map(|json_map| {
+                json_map.iter()
+                    .filter_map(|(name, value)| FilterSetObject::from_json(name, value, &mut diagnostics))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            version,
+            profiles,
+            filter_sets,
+            diagnostics
+        })
+    }
+
+    //---------------------------------------
+    // Read function
+    //---------------------------------------
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let json_str = fs::read_to_string(path)?;
+
+        Self::parse(&json_str)
+    }
+
+    //---------------------------------------
+    // Write function
+    //---------------------------------------
+    pub fn write(path: &Path, profiles: impl Iterator<Item = ProfileObject>, filter_sets: impl Iterator<Item = FilterSetObject>) -> io::Result<String> {
+        let profiles: JsonMap<String, JsonValue> = profiles
+            .map(|profile| profile.to_json())
+            .collect();
+
+        let filter_sets: JsonMap<String, JsonValue> = filter_sets
+            .map(|filter_set| filter_set.to_json())
+            .collect();
+
+        let json_str = to_string_pretty(&json!({
+            "version": CONFIG_VERSION,
+            "profiles": profiles,
+            "filter-sets": filter_sets
+        }))?;
+
+        // Write to temporary file and rename to make write atomic
+        let tmp_path = Self::suffixed_path(path, ".tmp");
+
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(json_str.as_bytes())?;
+        file.sync_all()?;
+
+        fs::rename(&tmp_path, path)?;
+
+        Ok(json_str)
+    }
+
+    //---------------------------------------
+    // Suffixed path helper function
+    //---------------------------------------
+    pub fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+        let mut suffixed: OsString = path.as_os_str().to_owned();
+        suffixed.push(suffix);
+
+        PathBuf::from(suffixed)
+    }
+
+    //---------------------------------------
+    // Backup function
+    //---------------------------------------
+    pub fn backup(path: &Path, version: u64) -> io::Result<PathBuf> {
+        let backup_path = Self::suffixed_path(path, &format!(".v{version}.bak"));
+
+        fs::copy(path, &backup_path)?;
+
+        Ok(backup_path)
+    }
+
+    //---------------------------------------
+    // Rotate backups function
+    //---------------------------------------
+    // Keeps a rolling history: rotates if the newest backup is older than interval
+    pub fn rotate_backups(path: &Path, interval: Duration) -> io::Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let newest_age = fs::metadata(Self::suffixed_path(path, ".bak.1"))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+
+        if newest_age.is_some_and(|age| age < interval) {
+            return Ok(());
+        }
+
+        // Shift existing backups, dropping the oldest one
+        for i in (1..BACKUP_COUNT).rev() {
+            let backup_path = Self::suffixed_path(path, &format!(".bak.{i}"));
+
+            if backup_path.exists() {
+                fs::rename(&backup_path, Self::suffixed_path(path, &format!(".bak.{}", i + 1)))?;
+            }
+        }
+
+        fs::copy(path, Self::suffixed_path(path, ".bak.1"))?;
+
+        Ok(())
+    }
+
+    //---------------------------------------
+    // Latest backup function
+    //---------------------------------------
+    pub fn latest_backup(path: &Path) -> Option<(PathBuf, Self)> {
+        (1..=BACKUP_COUNT)
+            .map(|i| Self::suffixed_path(path, &format!(".bak.{i}")))
+            .find_map(|backup_path| {
+                let config = Self::read(&backup_path).ok()?;
+
+                Some((backup_path, config))
+            })
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_backups_by_age() {
+        let dir = std::env::temp_dir().join(format!("syncer-config-test-{}", std::process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).expect("Could not create test dir");
+
+        let path = dir.join("config.json");
+        let backup = |i: usize| Config::suffixed_path(&path, &format!(".bak.{i}"));
+
+        fs::write(&path, "1").expect("Could not write config");
+        Config::rotate_backups(&path, BACKUP_INTERVAL).expect("Could not rotate backups");
+
+        assert_eq!(fs::read_to_string(backup(1)).ok().as_deref(), Some("1"));
+
+        // Newest backup is recent, keep it
+        fs::write(&path, "2").expect("Could not write config");
+        Config::rotate_backups(&path, BACKUP_INTERVAL).expect("Could not rotate backups");
+
+        assert_eq!(fs::read_to_string(backup(1)).ok().as_deref(), Some("1"));
+        assert!(!backup(2).exists());
+
+        // Interval elapsed, shift and drop the oldest backup
+        for i in 3..=BACKUP_COUNT + 2 {
+            Config::rotate_backups(&path, Duration::ZERO).expect("Could not rotate backups");
+
+            fs::write(&path, i.to_string()).expect("Could not write config");
+        }
+
+        assert_eq!(fs::read_to_string(backup(1)).ok().as_deref(), Some(&*(BACKUP_COUNT + 1).to_string()));
+        assert!(backup(BACKUP_COUNT).exists());
+        assert!(!backup(BACKUP_COUNT + 1).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
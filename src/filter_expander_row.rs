@@ -1,11 +1,18 @@
 use std::cell::{Cell, RefCell};
+use std::sync::OnceLock;
 
 use adw::subclass::prelude::*;
 use adw::prelude::*;
-use gtk::glib;
+use gtk::{gio, glib};
 use glib::{clone, closure_local};
+use glib::subclass::Signal;
 
 use crate::filter_row::FilterRow;
+use crate::filter_set_row::FilterSetRow;
+use crate::filter_set_object::FilterSetObject;
+use crate::profile_object::ProfileObject;
+use crate::filter_rule::{FilterRule, FilterFileFormat};
+use crate::utils::case;
 
 //------------------------------------------------------------------------------
 // MODULE: FilterExpanderRow
@@ -20,11 +27,23 @@ mod imp {
     #[properties(wrapper_type = super::FilterExpanderRow)]
     #[template(resource = "/com/github/Syncer/ui/filter_expander_row.ui")]
     pub struct FilterExpanderRow {
+        #[template_child]
+        pub(super) inherited_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) revert_button: TemplateChild<gtk::Button>,
         #[template_child]
         pub(super) add_button: TemplateChild<gtk::Button>,
 
         #[property(get, set)]
         filters: RefCell<Vec<String>>,
+        #[property(get, set)]
+        inherited: Cell<bool>,
+        #[property(get, set)]
+        overridden: Cell<bool>,
+        #[property(get, set, nullable)]
+        filter_set_model: RefCell<Option<gio::ListStore>>,
+        #[property(get, set, nullable)]
+        profile_model: RefCell<Option<gio::ListStore>>,
 
         pub(super) internal_change: Cell<bool>,
     }
@@ -40,6 +59,52 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+
+            // Filters import action
+            klass.install_action_async("filters.import", None, async |expander, _, _| {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Import Filters")
+                    .modal(true)
+                    .build();
+
+                let root = expander.root()
+                    .and_downcast::<gtk::Window>();
+
+                if let Ok(file) = dialog.open_future(root.as_ref()).await {
+                    expander.import_filters(&file).await;
+                }
+            });
+
+            // Filters insert set action
+            klass.install_action("filters.insert-set", None, |expander, _, _| {
+                expander.insert_set_dialog();
+            });
+
+            // Filters create set action
+            klass.install_action("filters.create-set", None, |expander, _, _| {
+                expander.create_set_dialog();
+            });
+
+            // Filters manage sets action
+            klass.install_action("filters.manage-sets", None, |expander, _, _| {
+                expander.manage_sets_dialog();
+            });
+
+            // Filters export action
+            klass.install_action_async("filters.export", None, async |expander, _, _| {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Export Filters")
+                    .modal(true)
+                    .initial_name(".rsync-filter")
+                    .build();
+
+                let root = expander.root()
+                    .and_downcast::<gtk::Window>();
+
+                if let Ok(file) = dialog.save_future(root.as_ref()).await {
+                    expander.export_filters(&file).await;
+                }
+            });
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -49,6 +114,19 @@ mod imp {
 
     #[glib::derived_properties]
     impl ObjectImpl for FilterExpanderRow {
+        //---------------------------------------
+        // Signals
+        //---------------------------------------
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("revert")
+                        .build(),
+                ]
+            })
+        }
+
         //---------------------------------------
         // Constructor
         //---------------------------------------
@@ -58,6 +136,7 @@ mod imp {
             let obj = self.obj();
 
             obj.setup_signals();
+            obj.setup_widgets();
         }
     }
 
@@ -86,43 +165,53 @@ impl FilterExpanderRow {
         // Filters property notify signal
         self.connect_filters_notify(|expander| {
             if !expander.imp().internal_change.get() {
-                let listbox = expander.listbox();
-
-                // Remove all filter rows
-                listbox.remove_all();
-
-                // Create new filter rows
-                for filter in expander.filters().iter() {
-                    let row = expander.new_filter_row(filter);
-
-                    listbox.append(&row);
-                }
+                expander.update_rows();
             }
 
             let filters = expander.filters();
 
+            expander.action_set_enabled("filters.export", !filters.is_empty());
+            expander.action_set_enabled("filters.create-set",
+                filters.iter().any(|filter| FilterSetObject::parse_reference(filter).is_none())
+            );
+
             expander.set_expanded(!filters.is_empty());
             expander.set_enable_expansion(!filters.is_empty());
 
             expander.set_subtitle(&filters.join(" "));
         });
 
+        // Filter set model property notify signal
+        self.connect_filter_set_model_notify(|expander| {
+            expander.update_rows();
+        });
+
+        // Revert button clicked signal
+        imp.revert_button.connect_clicked(clone!(
+            #[weak(rename_to = expander)] self,
+            move |_| {
+                expander.emit_by_name::<()>("revert", &[]);
+            }
+        ));
+
         // Add button clicked signal
         imp.add_button.connect_clicked(clone!(
             #[weak(rename_to = expander)] self,
             move |_| {
-                expander.filter_dialog(clone!(
+                FilterRow::filter_dialog(&expander, None, clone!(
                     #[weak] expander,
-                    move |filter| {
+                    move |rule| {
                         let imp = expander.imp();
 
                         imp.internal_change.set(true);
 
+                        let filter = rule.to_string();
+
                         let mut filters = expander.filters();
-                        filters.push(filter.to_owned());
+                        filters.push(filter.clone());
                         expander.set_filters(filters);
 
-                        let row = expander.new_filter_row(filter);
+                        let row = expander.new_filter_row(&filter);
                         expander.listbox().append(&row);
 
                         imp.internal_change.set(false);
@@ -132,6 +221,25 @@ impl FilterExpanderRow {
         ));
     }
 
+    //---------------------------------------
+    // Setup widgets
+    //---------------------------------------
+    fn setup_widgets(&self) {
+        let imp = self.imp();
+
+        // Bind inheritance properties to widgets
+        self.bind_property("inherited", &imp.inherited_label.get(), "visible")
+            .sync_create()
+            .build();
+
+        self.bind_property("overridden", &imp.revert_button.get(), "visible")
+            .sync_create()
+            .build();
+
+        self.action_set_enabled("filters.export", false);
+        self.action_set_enabled("filters.create-set", false);
+    }
+
     //---------------------------------------
     // Listbox function
     //---------------------------------------
@@ -147,6 +255,54 @@ impl FilterExpanderRow {
             .expect("Could not downcast to 'GtkListBox'")
     }
 
+    //---------------------------------------
+    // Update rows function
+    //---------------------------------------
+    fn update_rows(&self) {
+        let listbox = self.listbox();
+
+        // Remove all filter rows
+        listbox.remove_all();
+
+        // Create new filter rows
+        for filter in self.filters().iter() {
+            if let Some(name) = FilterSetObject::parse_reference(filter) {
+                let filter_set = self.filter_set_model()
+                    .and_then(|model| FilterSetObject::find(&model, name));
+
+                listbox.append(&self.new_filter_set_row(filter, filter_set.as_ref()));
+            } else {
+                listbox.append(&self.new_filter_row(filter));
+            }
+        }
+    }
+
+    //---------------------------------------
+    // New filter set row function
+    //---------------------------------------
+    fn new_filter_set_row(&self, filter: &str, filter_set: Option<&FilterSetObject>) -> FilterSetRow {
+        let row = FilterSetRow::new(filter, filter_set);
+
+        row.connect_closure("deleted", false, closure_local!(
+            #[weak(rename_to = expander)] self,
+            move |row: FilterSetRow| {
+                let imp = expander.imp();
+
+                imp.internal_change.set(true);
+
+                let mut filters = expander.filters();
+                filters.remove(row.index() as usize);
+                expander.set_filters(filters);
+
+                expander.listbox().remove(&row);
+
+                imp.internal_change.set(false);
+            }
+        ));
+
+        row
+    }
+
     //---------------------------------------
     // New filter row function
     //---------------------------------------
@@ -174,11 +330,43 @@ impl FilterExpanderRow {
             }
         ));
 
+        row.connect_activated(clone!(
+            #[weak(rename_to = expander)] self,
+            move |row| {
+                let rule = FilterRule::parse(&row.filter());
+
+                FilterRow::filter_dialog(&expander, rule.as_ref(), clone!(
+                    #[weak] expander,
+                    #[weak] row,
+                    move |rule| {
+                        let imp = expander.imp();
+
+                        imp.internal_change.set(true);
+
+                        let filter = rule.to_string();
+
+                        let mut filters = expander.filters();
+                        filters[row.index() as usize] = filter.clone();
+                        expander.set_filters(filters);
+
+                        row.set_filter(filter);
+
+                        imp.internal_change.set(false);
+                    }
+                ));
+            }
+        ));
+
         row.connect_closure("drop", false, closure_local!(
             #[weak(rename_to = expander)] self,
             move |row: FilterRow, drag_row: FilterRow| {
                 let imp = expander.imp();
 
+                // Ignore rows dragged from filter sets
+                if drag_row.parent() != row.parent() {
+                    return;
+                }
+
                 imp.internal_change.set(true);
 
                 let old_pos = drag_row.index();
@@ -201,37 +389,331 @@ impl FilterExpanderRow {
     }
 
     //---------------------------------------
-    // Filter dialog function
+    // Import filters function
+    //---------------------------------------
+    async fn import_filters(&self, file: &gio::File) {
+        let contents = match file.load_contents_future().await {
+            Ok((contents, _)) => String::from_utf8_lossy(&contents).into_owned(),
+            Err(error) => {
+                self.message_dialog("Error Importing Filters", &case::capitalize_first(&error.to_string()), &[]);
+                return;
+            }
+        };
+
+        let name = file.basename()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let (rules, errors) = FilterFileFormat::from_file_name(&name).parse_file(&contents);
+
+        // Append imported rules to filters
+        if !rules.is_empty() {
+            let mut filters = self.filters();
+            filters.extend(rules.iter().map(ToString::to_string));
+
+            self.set_filters(filters);
+        }
+
+        // Report untranslatable lines
+        if !errors.is_empty() {
+            self.message_dialog(
+                "Some Filters Not Imported",
+                &format!("{} filter(s) imported from \"{name}\". The following lines could not be translated:", rules.len()),
+                &errors
+            );
+        }
+    }
+
+    //---------------------------------------
+    // Export filters function
+    //---------------------------------------
+    async fn export_filters(&self, file: &gio::File) {
+        let filters = FilterSetObject::expand_filters(self.filter_set_model().as_ref(), &self.filters());
+
+        let rules: Vec<FilterRule> = filters.iter()
+            .filter_map(|filter| FilterRule::parse(filter))
+            .collect();
+
+        let contents = FilterFileFormat::write_file(&rules);
+
+        if let Err(error) = file.replace_contents_future(contents, None, false, gio::FileCreateFlags::REPLACE_DESTINATION).await {
+            self.message_dialog("Error Exporting Filters", &case::capitalize_first(&error.1.to_string()), &[]);
+        }
+    }
+
+    //---------------------------------------
+    // Message dialog function
     //---------------------------------------
-    fn filter_dialog<F>(&self, f: F)
-    where F: Fn(&str) + 'static {
-        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/filter_dialog.ui");
+    fn message_dialog(&self, heading: &str, body: &str, lines: &[String]) {
+        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/diagnostics_dialog.ui");
 
         let dialog: adw::AlertDialog = builder.object("dialog")
             .expect("Could not get object from resource");
 
-        let type_combo: adw::ComboRow = builder.object("type_combo")
+        let label: gtk::Label = builder.object("label")
             .expect("Could not get object from resource");
 
-        let filter_entry: adw::EntryRow = builder.object("filter_entry")
-            .expect("Could not get object from resource");
+        dialog.set_heading(Some(heading));
+        dialog.set_body(body);
+
+        label.set_label(&lines.join("\n"));
+        label.set_visible(!lines.is_empty());
+
+        dialog.present(Some(self));
+    }
+
+    //---------------------------------------
+    // Insert set dialog function
+    //---------------------------------------
+    fn insert_set_dialog(&self) {
+        let filter_sets: Vec<FilterSetObject> = self.filter_set_model()
+            .map(|model| {
+                model.iter::<FilterSetObject>()
+                    .flatten()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let names: Vec<String> = filter_sets.iter()
+            .map(FilterSetObject::name)
+            .collect();
+
+        let combo = adw::ComboRow::builder()
+            .title("Filter Set")
+            .model(&gtk::StringList::new(&names.iter().map(String::as_str).collect::<Vec<&str>>()))
+            .width_request(300)
+            .build();
+
+        let group = adw::PreferencesGroup::new();
+        group.add(&combo);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Insert Filter Set")
+            .body(if names.is_empty() { "No filter sets defined. Use \"Save as Filter Set\" to create one." } else { "Filters in the set are shared by all profiles that use it" })
+            .extra_child(&group)
+            .default_response("insert")
+            .close_response("cancel")
+            .build();
+
+        dialog.add_responses(&[("cancel", "_Cancel"), ("insert", "_Insert")]);
+        dialog.set_response_appearance("insert", adw::ResponseAppearance::Suggested);
+        dialog.set_response_enabled("insert", !names.is_empty());
+
+        dialog.connect_response(Some("insert"), clone!(
+            #[weak(rename_to = expander)] self,
+            move |_, _| {
+                let Some(filter_set) = filter_sets.get(combo.selected() as usize) else {
+                    return;
+                };
+
+                let mut filters = expander.filters();
+                filters.push(filter_set.reference());
 
-        filter_entry.connect_changed(clone!(
-            #[weak] dialog,
-            move |entry| {
-                dialog.set_response_enabled("add", !entry.text().is_empty());
+                expander.set_filters(filters);
             }
         ));
 
-        dialog.connect_response(Some("add"), move |_, _| {
-            let type_ = type_combo.selected_item()
-                .and_downcast::<gtk::StringObject>()
-                .expect("Could not downcast to 'GtkStringObject'")
-                .string();
+        dialog.present(Some(self));
+    }
 
-            f(&format!("--{}=\"{}\"", type_.to_ascii_lowercase(), filter_entry.text()));
-        });
+    //---------------------------------------
+    // Create set dialog function
+    //---------------------------------------
+    fn create_set_dialog(&self) {
+        let Some(model) = self.filter_set_model() else {
+            return;
+        };
+
+        let (dialog, name_entry) = FilterSetRow::name_dialog(&model, None);
+
+        dialog.set_heading(Some("Save as Filter Set"));
+        dialog.set_body("Move the filters of this profile to a new filter set that can be shared with other profiles");
+        dialog.set_response_label("add", "_Save");
+
+        dialog.connect_response(Some("add"), clone!(
+            #[weak(rename_to = expander)] self,
+            move |_, _| {
+                let filters = expander.filters();
+
+                // Move rules into filter set, keeping existing references
+                let (references, rules): (Vec<String>, Vec<String>) = filters.into_iter()
+                    .partition(|filter| FilterSetObject::parse_reference(filter).is_some());
+
+                let filter_set = FilterSetObject::new(&name_entry.text(), &rules);
+
+                model.append(&filter_set);
+
+                let mut filters = references;
+                filters.push(filter_set.reference());
+
+                expander.set_filters(filters);
+            }
+        ));
 
         dialog.present(Some(self));
     }
+
+    //---------------------------------------
+    // Manage sets dialog function
+    //---------------------------------------
+    fn manage_sets_dialog(&self) {
+        let Some(model) = self.filter_set_model() else {
+            return;
+        };
+
+        let group = adw::PreferencesGroup::builder()
+            .width_request(300)
+            .build();
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Filter Sets")
+            .body(if model.n_items() == 0 { "No filter sets defined. Use \"Save as Filter Set\" to create one." } else { "Renaming a filter set updates all profiles that use it" })
+            .extra_child(&group)
+            .default_response("close")
+            .build();
+
+        dialog.add_responses(&[("close", "_Close")]);
+
+        for filter_set in model.iter::<FilterSetObject>().flatten() {
+            let row = adw::ActionRow::builder()
+                .use_markup(false)
+                .subtitle(format!("{} filters", filter_set.filters().len()))
+                .build();
+
+            filter_set.bind_property("name", &row, "title")
+                .sync_create()
+                .build();
+
+            let rename_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("document-edit-symbolic")
+                .tooltip_text("Rename Filter Set")
+                .css_classes(["circular", "flat"])
+                .build();
+
+            let delete_button = gtk::Button::builder()
+                .valign(gtk::Align::Center)
+                .icon_name("stats-deleted-symbolic")
+                .tooltip_text("Delete Filter Set")
+                .css_classes(["circular", "flat"])
+                .build();
+
+            row.add_suffix(&rename_button);
+            row.add_suffix(&delete_button);
+
+            group.add(&row);
+
+            rename_button.connect_clicked(clone!(
+                #[weak(rename_to = expander)] self,
+                #[weak] dialog,
+                #[weak] filter_set,
+                move |_| {
+                    expander.rename_set_dialog(&dialog, &filter_set);
+                }
+            ));
+
+            delete_button.connect_clicked(clone!(
+                #[weak(rename_to = expander)] self,
+                #[weak] dialog,
+                #[weak] group,
+                #[weak] row,
+                #[weak] filter_set,
+                move |_| {
+                    expander.delete_set_dialog(&dialog, &filter_set, move || {
+                        group.remove(&row);
+                    });
+                }
+            ));
+        }
+
+        dialog.present(Some(self));
+    }
+
+    //---------------------------------------
+    // Rename set dialog function
+    //---------------------------------------
+    fn rename_set_dialog(&self, parent: &adw::AlertDialog, filter_set: &FilterSetObject) {
+        let Some(model) = self.filter_set_model() else {
+            return;
+        };
+
+        let (dialog, name_entry) = FilterSetRow::name_dialog(&model, Some(&filter_set.name()));
+
+        dialog.set_heading(Some("Rename Filter Set"));
+        dialog.set_response_label("add", "_Rename");
+
+        dialog.connect_response(Some("add"), clone!(
+            #[weak(rename_to = expander)] self,
+            #[weak] filter_set,
+            move |_, _| {
+                let old_reference = filter_set.reference();
+
+                filter_set.set_name(name_entry.text());
+
+                // Update references in profiles (inherited filters follow their template)
+                for profile in expander.profile_model().iter().flat_map(|model| model.iter::<ProfileObject>().flatten()) {
+                    profile.replace_filter(&old_reference, &filter_set.reference());
+                }
+            }
+        ));
+
+        dialog.present(Some(parent));
+    }
+
+    //---------------------------------------
+    // Delete set dialog function
+    //---------------------------------------
+    fn delete_set_dialog<F>(&self, parent: &adw::AlertDialog, filter_set: &FilterSetObject, f: F)
+    where F: Fn() + 'static {
+        let Some(model) = self.filter_set_model() else {
+            return;
+        };
+
+        let reference = filter_set.reference();
+
+        let users: Vec<String> = self.profile_model().iter()
+            .flat_map(|model| model.iter::<ProfileObject>().flatten())
+            .filter(|profile| profile.filters().contains(&reference))
+            .map(|profile| profile.name())
+            .collect();
+
+        // Block deleting filter sets that are still in use
+        if !users.is_empty() {
+            let dialog = adw::AlertDialog::builder()
+                .heading("Filter Set In Use")
+                .body(format!("The \"{}\" filter set is used by the following profiles. Remove it from these profiles before deleting it.\n\n{}",
+                    filter_set.name(), users.join("\n")))
+                .default_response("close")
+                .build();
+
+            dialog.add_responses(&[("close", "_Close")]);
+
+            dialog.present(Some(parent));
+
+            return;
+        }
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Delete Filter Set?")
+            .body(format!("Permanently delete the \"{}\" filter set.", filter_set.name()))
+            .default_response("delete")
+            .close_response("cancel")
+            .build();
+
+        dialog.add_responses(&[("cancel", "_Cancel"), ("delete", "_Delete")]);
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+
+        dialog.connect_response(Some("delete"), clone!(
+            #[weak] filter_set,
+            move |_, _| {
+                if let Some(pos) = model.find(&filter_set) {
+                    model.remove(pos);
+                }
+
+                f();
+            }
+        ));
+
+        dialog.present(Some(parent));
+    }
 }
@@ -7,7 +7,7 @@ use gtk::{gdk, glib};
 use glib::clone;
 use glib::subclass::Signal;
 
-use crate::utils::case;
+use crate::filter_rule::{FilterRule, FilterType, MODIFIERS};
 
 //------------------------------------------------------------------------------
 // MODULE: FilterRow
@@ -22,6 +22,8 @@ mod imp {
     #[properties(wrapper_type = super::FilterRow)]
     #[template(resource = "/com/github/Syncer/ui/filter_row.ui")]
     pub struct FilterRow {
+        #[template_child]
+        pub(super) modifiers_label: TemplateChild<gtk::Label>,
         #[template_child]
         pub(super) delete_button: TemplateChild<gtk::Button>,
 
@@ -122,19 +124,45 @@ impl FilterRow {
     // Setup widgets
     //---------------------------------------
     fn setup_widgets(&self) {
+        let imp = self.imp();
+
         // Bind filter property to widget
         self.bind_property("filter", self, "title")
             .transform_to(|_, filter: String| {
-                filter.split_once("=")
-                    .map(|(type_, _)| case::capitalize_first(type_.trim_start_matches("--")))
+                FilterRule::parse(&filter)
+                    .map(|rule| rule.filter_type.label().to_owned())
             })
             .sync_create()
             .build();
 
         self.bind_property("filter", self, "subtitle")
             .transform_to(|_, filter: String| {
-                filter.split_once("=")
-                    .map(|(_, filter)| filter.trim_matches('"').to_owned())
+                Some(FilterRule::parse(&filter)
+                    .map_or(filter, |rule| rule.pattern))
+            })
+            .sync_create()
+            .build();
+
+        self.bind_property("filter", &imp.modifiers_label.get(), "label")
+            .transform_to(|_, filter: String| {
+                FilterRule::parse(&filter)
+                    .map(|rule| rule.modifiers)
+            })
+            .sync_create()
+            .build();
+
+        self.bind_property("filter", &imp.modifiers_label.get(), "visible")
+            .transform_to(|_, filter: String| {
+                FilterRule::parse(&filter)
+                    .map(|rule| !rule.modifiers.is_empty())
+            })
+            .sync_create()
+            .build();
+
+        self.bind_property("filter", &imp.modifiers_label.get(), "tooltip-text")
+            .transform_to(|_, filter: String| {
+                FilterRule::parse(&filter)
+                    .map(|rule| rule.modifier_descriptions().join("\n"))
             })
             .sync_create()
             .build();
@@ -199,4 +227,104 @@ impl FilterRow {
         // Add drop target to row
         self.add_controller(drop_target);
     }
+
+    //---------------------------------------
+    // Filter dialog function
+    //---------------------------------------
+    pub fn filter_dialog<F>(parent: &impl IsA<gtk::Widget>, rule: Option<&FilterRule>, f: F)
+    where F: Fn(&FilterRule) + 'static {
+        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/filter_dialog.ui");
+
+        let dialog: adw::AlertDialog = builder.object("dialog")
+            .expect("Could not get object from resource");
+
+        let type_combo: adw::ComboRow = builder.object("type_combo")
+            .expect("Could not get object from resource");
+
+        let filter_entry: adw::EntryRow = builder.object("filter_entry")
+            .expect("Could not get object from resource");
+
+        let modifiers_group: adw::PreferencesGroup = builder.object("modifiers_group")
+            .expect("Could not get object from resource");
+
+        // Create modifier switches
+        let modifier_switches: Vec<(char, adw::SwitchRow)> = MODIFIERS.iter()
+            .map(|&(modifier, desc)| {
+                let switch = adw::SwitchRow::builder()
+                    .title(desc)
+                    .subtitle(modifier.to_string())
+                    .build();
+
+                modifiers_group.add(&switch);
+
+                (modifier, switch)
+            })
+            .collect();
+
+        // Initialize widgets from rule
+        if let Some(rule) = rule {
+            dialog.set_heading(Some("Edit Filter"));
+            dialog.set_response_label("add", "_Save");
+
+            type_combo.set_selected(rule.filter_type as u32);
+            filter_entry.set_text(&rule.pattern);
+
+            for (modifier, switch) in &modifier_switches {
+                switch.set_active(rule.modifiers.contains(*modifier));
+            }
+        }
+
+        // Update widgets for filter type
+        let update_widgets = clone!(
+            #[weak] dialog,
+            #[weak] type_combo,
+            #[weak] filter_entry,
+            #[weak] modifiers_group,
+            #[strong] modifier_switches,
+            move || {
+                let filter_type = FilterType::from_repr(type_combo.selected())
+                    .unwrap_or_default();
+
+                type_combo.set_subtitle(filter_type.desc());
+
+                filter_entry.set_visible(filter_type.has_pattern());
+                filter_entry.set_title(if filter_type.is_merge() { "File" } else { "Pattern" });
+
+                for (modifier, switch) in &modifier_switches {
+                    switch.set_visible(filter_type.modifiers().contains(*modifier));
+                }
+
+                modifiers_group.set_visible(!filter_type.modifiers().is_empty());
+
+                dialog.set_response_enabled("add", !filter_type.has_pattern() || !filter_entry.text().is_empty());
+            }
+        );
+
+        update_widgets();
+
+        type_combo.connect_selected_notify(clone!(
+            #[strong] update_widgets,
+            move |_| {
+                update_widgets();
+            }
+        ));
+
+        filter_entry.connect_changed(move |_| {
+            update_widgets();
+        });
+
+        dialog.connect_response(Some("add"), move |_, _| {
+            let filter_type = FilterType::from_repr(type_combo.selected())
+                .unwrap_or_default();
+
+            let modifiers: String = modifier_switches.iter()
+                .filter(|(_, switch)| switch.is_visible() && switch.is_active())
+                .map(|(modifier, _)| *modifier)
+                .collect();
+
+            f(&FilterRule::new(filter_type, &modifiers, &filter_entry.text()));
+        });
+
+        dialog.present(Some(parent));
+    }
 }
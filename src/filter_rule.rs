@@ -0,0 +1,429 @@
+use std::fmt;
+
+use strum::{EnumProperty, FromRepr};
+
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+pub const MODIFIERS: [(char, &str); 12] = [
+    ('/', "Match against absolute path"),
+    ('!', "Apply if pattern does not match"),
+    ('s', "Apply to sending side only"),
+    ('r', "Apply to receiving side only"),
+    ('p', "Perishable (ignored in directories being deleted)"),
+    ('x', "Apply to extended attribute names only"),
+    ('-', "Merge file contains exclude patterns only"),
+    ('+', "Merge file contains include patterns only"),
+    ('e', "Exclude merge file from transfer"),
+    ('n', "Rules are not inherited by subdirectories"),
+    ('w', "Split rules on whitespace"),
+    ('C', "Read rules as CVS ignore file"),
+];
+
+//------------------------------------------------------------------------------
+// ENUM: FilterType
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy, EnumProperty, FromRepr)]
+#[repr(u32)]
+pub enum FilterType {
+    #[default]
+    #[strum(props(Name="exclude", Short="-", Label="Exclude", Modifiers="/!srpx", Desc="Exclude matching files"))]
+    Exclude,
+    #[strum(props(Name="include", Short="+", Label="Include", Modifiers="/!srpx", Desc="Include matching files"))]
+    Include,
+    #[strum(props(Name="merge", Short=".", Label="Merge", Modifiers="-+enwC/srpx", Desc="Read rules from file"))]
+    Merge,
+    #[strum(props(Name="dir-merge", Short=":", Label="Dir-Merge", Modifiers="-+enwC/srpx", Desc="Read rules from file in each directory"))]
+    DirMerge,
+    #[strum(props(Name="hide", Short="H", Label="Hide", Modifiers="/!px", Desc="Hide matching files from transfer"))]
+    Hide,
+    #[strum(props(Name="show", Short="S", Label="Show", Modifiers="/!px", Desc="Don't hide matching files"))]
+    Show,
+    #[strum(props(Name="protect", Short="P", Label="Protect", Modifiers="/!px", Desc="Protect matching files from deletion"))]
+    Protect,
+    #[strum(props(Name="risk", Short="R", Label="Risk", Modifiers="/!px", Desc="Don't protect matching files"))]
+    Risk,
+    #[strum(props(Name="clear", Short="!", Label="Clear", Modifiers="", Desc="Clear current include/exclude list"))]
+    Clear,
+}
+
+impl FilterType {
+    pub fn name(self) -> &'static str {
+        self.get_str("Name").unwrap_or_default()
+    }
+
+    pub fn short(self) -> &'static str {
+        self.get_str("Short").unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        self.get_str("Label").unwrap_or_default()
+    }
+
+    pub fn modifiers(self) -> &'static str {
+        self.get_str("Modifiers").unwrap_or_default()
+    }
+
+    pub fn desc(self) -> &'static str {
+        self.get_str("Desc").unwrap_or_default()
+    }
+
+    pub fn has_pattern(self) -> bool {
+        self != Self::Clear
+    }
+
+    pub fn is_merge(self) -> bool {
+        matches!(self, Self::Merge | Self::DirMerge)
+    }
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        (0..).map_while(Self::from_repr)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|type_| type_.name() == name)
+    }
+
+    fn from_short(short: char) -> Option<Self> {
+        Self::iter().find(|type_| type_.short().starts_with(short))
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: FilterRule
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+pub struct FilterRule {
+    pub filter_type: FilterType,
+    pub modifiers: String,
+    pub pattern: String
+}
+
+impl FilterRule {
+    //---------------------------------------
+    // New function
+    //---------------------------------------
+    pub fn new(filter_type: FilterType, modifiers: &str, pattern: &str) -> Self {
+        // Keep allowed modifiers only, in canonical order
+        let modifiers = filter_type.modifiers().chars()
+            .filter(|&c| modifiers.contains(c))
+            .collect();
+
+        let pattern = if filter_type.has_pattern() { pattern.to_owned() } else { String::new() };
+
+        Self {
+            filter_type,
+            modifiers,
+            pattern
+        }
+    }
+
+    //---------------------------------------
+    // Parse function
+    //---------------------------------------
+    pub fn parse(rule: &str) -> Option<Self> {
+        // Legacy option format (--exclude="pattern", --include="pattern", --filter="rule")
+        if let Some((option, value)) = rule.strip_prefix("--").and_then(|rule| rule.split_once('=')) {
+            let value = value.trim_matches(['"', '\'']);
+
+            return match option {
+                "exclude" => Some(Self::new(FilterType::Exclude, "", value)),
+                "include" => Some(Self::new(FilterType::Include, "", value)),
+                "filter" => Self::parse(value),
+                _ => None
+            };
+        }
+
+        // Split rule into rule name with modifiers and pattern
+        let (head, pattern) = rule.split_once([' ', '_'])
+            .unwrap_or((rule, ""));
+
+        let (filter_type, modifiers) = if let Some((name, modifiers)) = head.split_once(',') {
+            (FilterType::from_name(name)?, modifiers)
+        } else if let Some(filter_type) = FilterType::from_name(head) {
+            (filter_type, "")
+        } else {
+            let mut chars = head.chars();
+
+            (FilterType::from_short(chars.next()?)?, chars.as_str())
+        };
+
+        // Check modifiers and pattern
+        if !modifiers.chars().all(|c| filter_type.modifiers().contains(c)) {
+            return None;
+        }
+
+        if filter_type.has_pattern() == pattern.is_empty() {
+            return None;
+        }
+
+        Some(Self::new(filter_type, modifiers, pattern))
+    }
+
+    //---------------------------------------
+    // Arg function
+    //---------------------------------------
+    pub fn arg(&self, quoted: bool) -> String {
+        let quote = if quoted { "\"" } else { "" };
+
+        format!("--filter={quote}{self}{quote}")
+    }
+
+    //---------------------------------------
+    // Modifier descriptions function
+    //---------------------------------------
+    pub fn modifier_descriptions(&self) -> Vec<&'static str> {
+        MODIFIERS.iter()
+            .filter(|(c, _)| self.modifiers.contains(*c))
+            .map(|(_, desc)| *desc)
+            .collect()
+    }
+}
+
+impl fmt::Display for FilterRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.filter_type.name())?;
+
+        if !self.modifiers.is_empty() {
+            write!(f, ",{}", self.modifiers)?;
+        }
+
+        if !self.pattern.is_empty() {
+            write!(f, " {}", self.pattern)?;
+        }
+
+        Ok(())
+    }
+}
+
+//------------------------------------------------------------------------------
+// ENUM: FilterFileFormat
+//------------------------------------------------------------------------------
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum FilterFileFormat {
+    Gitignore,
+    RsyncFilter,
+    ExcludeList,
+}
+
+impl FilterFileFormat {
+    //---------------------------------------
+    // From file name function
+    //---------------------------------------
+    pub fn from_file_name(name: &str) -> Self {
+        if name.ends_with("gitignore") || name.ends_with(".ignore") {
+            Self::Gitignore
+        } else if name.contains("filter") {
+            Self::RsyncFilter
+        } else {
+            Self::ExcludeList
+        }
+    }
+
+    //---------------------------------------
+    // Parse file function
+    //---------------------------------------
+    // Returns translated rules and descriptions of untranslatable lines
+    pub fn parse_file(self, contents: &str) -> (Vec<FilterRule>, Vec<String>) {
+        let mut rules = vec![];
+        let mut errors = vec![];
+
+        for (i, line) in contents.lines().enumerate() {
+            let result = match self {
+                Self::Gitignore => Self::parse_gitignore_line(line),
+                Self::RsyncFilter => Self::parse_rsync_filter_line(line),
+                Self::ExcludeList => Self::parse_exclude_line(line),
+            };
+
+            match result {
+                Ok(Some(rule)) => rules.push(rule),
+                Ok(None) => {},
+                Err(reason) => errors.push(format!("Line {}: \"{}\" ({reason})", i + 1, line.trim()))
+            }
+        }
+
+        // Gitignore is last-match-wins, rsync filters are first-match-wins
+        if self == Self::Gitignore {
+            rules.reverse();
+        }
+
+        (rules, errors)
+    }
+
+    //---------------------------------------
+    // Parse gitignore line helper function
+    //---------------------------------------
+    fn parse_gitignore_line(line: &str) -> Result<Option<FilterRule>, &'static str> {
+        // Skip blank lines and comments
+        if line.trim().is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        // Trailing spaces are ignored unless escaped
+        if line.trim_end().ends_with('\\') && line.len() > line.trim_end().len() {
+            return Err("escaped trailing whitespace is not supported by rsync");
+        }
+
+        let line = line.trim_end();
+
+        // Negation
+        let (filter_type, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (FilterType::Include, pattern),
+            None => (FilterType::Exclude, line),
+        };
+
+        // Escaped leading characters
+        let pattern = pattern.strip_prefix('\\')
+            .filter(|pattern| pattern.starts_with(['#', '!']))
+            .unwrap_or(pattern);
+
+        // Directory only patterns keep their trailing slash
+        let (body, dir_only) = match pattern.strip_suffix('/') {
+            Some(body) => (body, true),
+            None => (pattern, false),
+        };
+
+        if body.is_empty() || body == "/" {
+            return Err("empty pattern");
+        }
+
+        // Patterns with a leading or middle slash are anchored to the .gitignore directory
+        let body = if let Some(body) = body.strip_prefix("**/") {
+            body.to_owned()
+        } else if body.contains('/') && !body.starts_with('/') {
+            format!("/{body}")
+        } else {
+            body.to_owned()
+        };
+
+        let pattern = if dir_only { format!("{body}/") } else { body };
+
+        Ok(Some(FilterRule::new(filter_type, "", &pattern)))
+    }
+
+    //---------------------------------------
+    // Parse rsync filter line helper function
+    //---------------------------------------
+    fn parse_rsync_filter_line(line: &str) -> Result<Option<FilterRule>, &'static str> {
+        // Skip blank lines and comments
+        if line.trim().is_empty() || line.starts_with(['#', ';']) {
+            return Ok(None);
+        }
+
+        FilterRule::parse(line)
+            .map(Some)
+            .ok_or("unsupported rule or modifier")
+    }
+
+    //---------------------------------------
+    // Parse exclude line helper function
+    //---------------------------------------
+    fn parse_exclude_line(line: &str) -> Result<Option<FilterRule>, &'static str> {
+        // Skip blank lines and comments
+        if line.trim().is_empty() || line.starts_with(['#', ';']) {
+            return Ok(None);
+        }
+
+        // Lines starting with "- " or "+ " are explicit rules
+        if line.starts_with("- ") || line.starts_with("+ ") {
+            return Self::parse_rsync_filter_line(line);
+        }
+
+        Ok(Some(FilterRule::new(FilterType::Exclude, "", line)))
+    }
+
+    //---------------------------------------
+    // Write file function
+    //---------------------------------------
+    pub fn write_file(rules: &[FilterRule]) -> String {
+        let mut contents = String::from("# rsync filter rules exported by Syncer\n");
+
+        for rule in rules {
+            contents.push_str(&rule.to_string());
+            contents.push('\n');
+        }
+
+        contents
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trip() {
+        let rules = [
+            "exclude *.log",
+            "include,/ /home/",
+            "merge,e .rsync-filter",
+            "dir-merge,-n .gitignore",
+            "dir-merge,+ .includes",
+            "hide,!p tmp",
+            "risk,x user.*",
+            "clear",
+        ];
+
+        for rule in rules {
+            let parsed = FilterRule::parse(rule)
+                .unwrap_or_else(|| panic!("Could not parse \"{rule}\""));
+
+            assert_eq!(parsed.to_string(), rule);
+            assert_eq!(FilterRule::parse(&parsed.to_string()), Some(parsed));
+        }
+    }
+
+    #[test]
+    fn parse_short_forms() {
+        let cases = [
+            ("- *.tmp", FilterType::Exclude, "", "*.tmp"),
+            ("+/ /etc/", FilterType::Include, "/", "/etc/"),
+            (":- .gitignore", FilterType::DirMerge, "-", ".gitignore"),
+            ("dir-merge,- .rsync-filter", FilterType::DirMerge, "-", ".rsync-filter"),
+            (".e .rules", FilterType::Merge, "e", ".rules"),
+            ("exclude_cache", FilterType::Exclude, "", "cache"),
+            ("!", FilterType::Clear, "", ""),
+        ];
+
+        for (rule, filter_type, modifiers, pattern) in cases {
+            assert_eq!(FilterRule::parse(rule), Some(FilterRule::new(filter_type, modifiers, pattern)), "{rule}");
+        }
+    }
+
+    #[test]
+    fn parse_legacy_options() {
+        assert_eq!(FilterRule::parse("--exclude=\"*.o\""), Some(FilterRule::new(FilterType::Exclude, "", "*.o")));
+        assert_eq!(FilterRule::parse("--include='src/'"), Some(FilterRule::new(FilterType::Include, "", "src/")));
+        assert_eq!(FilterRule::parse("--filter=\"- build/\""), Some(FilterRule::new(FilterType::Exclude, "", "build/")));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for rule in ["exclude", "clear foo", "exclude,e foo", "include,- foo", "bogus foo", "--delete=foo", ""] {
+            assert_eq!(FilterRule::parse(rule), None, "{rule}");
+        }
+    }
+
+    #[test]
+    fn parse_gitignore_order() {
+        let (rules, errors) = FilterFileFormat::Gitignore.parse_file("*.log\n# comment\n!keep.log\nbuild/\n");
+
+        assert!(errors.is_empty());
+        assert_eq!(rules, vec![
+            FilterRule::new(FilterType::Exclude, "", "build/"),
+            FilterRule::new(FilterType::Include, "", "keep.log"),
+            FilterRule::new(FilterType::Exclude, "", "*.log"),
+        ]);
+    }
+
+    #[test]
+    fn new_orders_modifiers() {
+        let rule = FilterRule::new(FilterType::DirMerge, "ne-z", ".filter");
+
+        assert_eq!(rule.modifiers, "-en");
+        assert_eq!(rule.to_string(), "dir-merge,-en .filter");
+    }
+}
@@ -0,0 +1,165 @@
+use std::cell::RefCell;
+
+use gtk::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gio, glib};
+
+use serde_json::{json, Value as JsonValue};
+
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+const REFERENCE_PREFIX: char = '@';
+
+//------------------------------------------------------------------------------
+// MODULE: FilterSetObject
+//------------------------------------------------------------------------------
+mod imp {
+    use super::*;
+
+    //---------------------------------------
+    // Private structure
+    //---------------------------------------
+    #[derive(Default, glib::Properties)]
+    #[properties(wrapper_type = super::FilterSetObject)]
+    pub struct FilterSetObject {
+        #[property(get, set)]
+        name: RefCell<String>,
+        #[property(get, set)]
+        filters: RefCell<Vec<String>>,
+    }
+
+    //---------------------------------------
+    // Subclass
+    //---------------------------------------
+    #[glib::object_subclass]
+    impl ObjectSubclass for FilterSetObject {
+        const NAME: &'static str = "FilterSetObject";
+        type Type = super::FilterSetObject;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for FilterSetObject {}
+}
+
+//------------------------------------------------------------------------------
+// IMPLEMENTATION: FilterSetObject
+//------------------------------------------------------------------------------
+glib::wrapper! {
+    pub struct FilterSetObject(ObjectSubclass<imp::FilterSetObject>);
+}
+
+impl FilterSetObject {
+    //---------------------------------------
+    // New function
+    //---------------------------------------
+    pub fn new(name: &str, filters: &[String]) -> Self {
+        glib::Object::builder()
+            .property("name", name)
+            .property("filters", filters.to_vec())
+            .build()
+    }
+
+    //---------------------------------------
+    // From json function
+    //---------------------------------------
+    pub fn from_json(name: &str, json_value: &JsonValue, diagnostics: &mut Vec<String>) -> Option<Self> {
+        let filters: Option<Vec<String>> = json_value.as_array()
+            .and_then(|array| {
+                array.iter()
+                    .map(|value| value.as_str().map(ToOwned::to_owned))
+                    .collect()
+            });
+
+        if filters.is_none() {
+            diagnostics.push(format!("Filter set \"{name}\": invalid filter list ignored"));
+        }
+
+        filters.map(|filters| Self::new(name, &filters))
+    }
+
+    //---------------------------------------
+    // To json function
+    //---------------------------------------
+    pub fn to_json(&self) -> (String, JsonValue) {
+        (self.name(), json!(self.filters()))
+    }
+
+    //---------------------------------------
+    // Reference function
+    //---------------------------------------
+    pub fn reference(&self) -> String {
+        format!("{REFERENCE_PREFIX}{}", self.name())
+    }
+
+    //---------------------------------------
+    // Parse reference function
+    //---------------------------------------
+    pub fn parse_reference(filter: &str) -> Option<&str> {
+        filter.strip_prefix(REFERENCE_PREFIX)
+    }
+
+    //---------------------------------------
+    // Find function
+    //---------------------------------------
+    pub fn find(model: &gio::ListStore, name: &str) -> Option<Self> {
+        model.iter::<Self>()
+            .flatten()
+            .find(|set| set.name() == name)
+    }
+
+    //---------------------------------------
+    // Expand filters function
+    //---------------------------------------
+    // Replaces filter set references with the filters of the set
+    pub fn expand_filters(model: Option<&gio::ListStore>, filters: &[String]) -> Vec<String> {
+        filters.iter()
+            .flat_map(|filter| {
+                match Self::parse_reference(filter) {
+                    Some(name) => model.and_then(|model| Self::find(model, name))
+                        .map(|set| set.filters())
+                        .unwrap_or_default(),
+                    None => vec![filter.clone()]
+                }
+            })
+            .collect()
+    }
+
+    //---------------------------------------
+    // Unresolved references function
+    //---------------------------------------
+    // Filter set references without a matching set (expand to no filters)
+    pub fn unresolved_references(model: Option<&gio::ListStore>, filters: &[String]) -> Vec<String> {
+        filters.iter()
+            .filter(|filter| {
+                Self::parse_reference(filter)
+                    .is_some_and(|name| model.and_then(|model| Self::find(model, name)).is_none())
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_filters(filters: &[&str]) -> Vec<String> {
+        filters.iter().map(|&filter| filter.to_owned()).collect()
+    }
+
+    #[test]
+    fn expand_and_unresolved_references() {
+        let model = gio::ListStore::new::<FilterSetObject>();
+        model.append(&FilterSetObject::new("build", &to_filters(&["- target/", "- *.o"])));
+
+        let filters = to_filters(&["+ src/", "@build", "@missing", "- *"]);
+
+        assert_eq!(FilterSetObject::expand_filters(Some(&model), &filters), ["+ src/", "- target/", "- *.o", "- *"]);
+        assert_eq!(FilterSetObject::unresolved_references(Some(&model), &filters), ["@missing"]);
+        assert_eq!(FilterSetObject::unresolved_references(None, &filters), ["@build", "@missing"]);
+    }
+}
@@ -0,0 +1,296 @@
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{gio, glib};
+use glib::{clone, closure_local};
+use glib::subclass::Signal;
+
+use crate::{
+    filter_row::FilterRow,
+    filter_rule::FilterRule,
+    filter_set_object::FilterSetObject
+};
+
+//------------------------------------------------------------------------------
+// MODULE: FilterSetRow
+//------------------------------------------------------------------------------
+mod imp {
+    use super::*;
+
+    //---------------------------------------
+    // Private structure
+    //---------------------------------------
+    #[derive(Default, gtk::CompositeTemplate, glib::Properties)]
+    #[properties(wrapper_type = super::FilterSetRow)]
+    #[template(resource = "/com/github/Syncer/ui/filter_set_row.ui")]
+    pub struct FilterSetRow {
+        #[template_child]
+        pub(super) add_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub(super) delete_button: TemplateChild<gtk::Button>,
+
+        #[property(get, set, construct_only)]
+        filter: RefCell<String>,
+        #[property(get, set, nullable)]
+        filter_set: RefCell<Option<FilterSetObject>>,
+
+        pub(super) rows: RefCell<Vec<FilterRow>>,
+        pub(super) filters_handler: RefCell<Option<(FilterSetObject, glib::SignalHandlerId)>>,
+    }
+
+    //---------------------------------------
+    // Subclass
+    //---------------------------------------
+    #[glib::object_subclass]
+    impl ObjectSubclass for FilterSetRow {
+        const NAME: &'static str = "FilterSetRow";
+        type Type = super::FilterSetRow;
+        type ParentType = adw::ExpanderRow;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for FilterSetRow {
+        //---------------------------------------
+        // Signals
+        //---------------------------------------
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("deleted")
+                        .build(),
+                ]
+            })
+        }
+
+        //---------------------------------------
+        // Constructor
+        //---------------------------------------
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+
+            obj.setup_signals();
+
+            // Connect to initial filter set
+            obj.notify_filter_set();
+        }
+
+        //---------------------------------------
+        // Dispose function
+        //---------------------------------------
+        fn dispose(&self) {
+            if let Some((filter_set, handler)) = self.filters_handler.take() {
+                filter_set.disconnect(handler);
+            }
+        }
+    }
+
+    impl WidgetImpl for FilterSetRow {}
+    impl ListBoxRowImpl for FilterSetRow {}
+    impl PreferencesRowImpl for FilterSetRow {}
+    impl ExpanderRowImpl for FilterSetRow {}
+}
+
+//------------------------------------------------------------------------------
+// IMPLEMENTATION: FilterSetRow
+//------------------------------------------------------------------------------
+glib::wrapper! {
+    pub struct FilterSetRow(ObjectSubclass<imp::FilterSetRow>)
+        @extends adw::ExpanderRow, adw::PreferencesRow, gtk::ListBoxRow, gtk::Widget,
+        @implements gtk::Accessible, gtk::Actionable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl FilterSetRow {
+    //---------------------------------------
+    // New function
+    //---------------------------------------
+    pub fn new(filter: &str, filter_set: Option<&FilterSetObject>) -> Self {
+        glib::Object::builder()
+            .property("filter", filter)
+            .property("filter-set", filter_set)
+            .build()
+    }
+
+    //---------------------------------------
+    // Setup signals
+    //---------------------------------------
+    fn setup_signals(&self) {
+        let imp = self.imp();
+
+        // Filter set property notify signal
+        self.connect_filter_set_notify(|row| {
+            let imp = row.imp();
+
+            if let Some((filter_set, handler)) = imp.filters_handler.take() {
+                filter_set.disconnect(handler);
+            }
+
+            if let Some(filter_set) = row.filter_set() {
+                let handler = filter_set.connect_filters_notify(clone!(
+                    #[weak] row,
+                    move |_| {
+                        row.update_rows();
+                    }
+                ));
+
+                imp.filters_handler.replace(Some((filter_set, handler)));
+            }
+
+            row.update_rows();
+        });
+
+        // Add button clicked signal
+        imp.add_button.connect_clicked(clone!(
+            #[weak(rename_to = row)] self,
+            move |_| {
+                FilterRow::filter_dialog(&row, None, clone!(
+                    #[weak] row,
+                    move |rule| {
+                        if let Some(filter_set) = row.filter_set() {
+                            let mut filters = filter_set.filters();
+                            filters.push(rule.to_string());
+
+                            filter_set.set_filters(filters);
+                        }
+                    }
+                ));
+            }
+        ));
+
+        // Delete button clicked signal
+        imp.delete_button.connect_clicked(clone!(
+            #[weak(rename_to = row)] self,
+            move |_| {
+                row.emit_by_name::<()>("deleted", &[]);
+            }
+        ));
+    }
+
+    //---------------------------------------
+    // Update rows function
+    //---------------------------------------
+    fn update_rows(&self) {
+        let imp = self.imp();
+
+        let name = FilterSetObject::parse_reference(&self.filter())
+            .unwrap_or_default()
+            .to_owned();
+
+        self.set_title(&glib::markup_escape_text(&name));
+
+        // Remove filter rows
+        for row in imp.rows.take() {
+            self.remove(&row);
+        }
+
+        let Some(filter_set) = self.filter_set() else {
+            self.set_subtitle("Filter set not found");
+            self.set_enable_expansion(false);
+            imp.add_button.set_sensitive(false);
+
+            return;
+        };
+
+        let filters = filter_set.filters();
+
+        self.set_subtitle(&format!("Shared filter set ({} filters)", filters.len()));
+        self.set_enable_expansion(!filters.is_empty());
+        imp.add_button.set_sensitive(true);
+
+        // Create filter rows (changes apply to all profiles using the set)
+        let rows: Vec<FilterRow> = filters.iter()
+            .enumerate()
+            .map(|(i, filter)| {
+                let row = FilterRow::new(filter);
+
+                row.connect_closure("deleted", false, closure_local!(
+                    #[weak] filter_set,
+                    move |_: FilterRow| {
+                        let mut filters = filter_set.filters();
+
+                        if i < filters.len() {
+                            filters.remove(i);
+                        }
+
+                        filter_set.set_filters(filters);
+                    }
+                ));
+
+                row.connect_activated(clone!(
+                    #[weak] filter_set,
+                    move |row| {
+                        let rule = FilterRule::parse(&row.filter());
+
+                        FilterRow::filter_dialog(row, rule.as_ref(), clone!(
+                            #[weak] filter_set,
+                            move |rule| {
+                                let mut filters = filter_set.filters();
+
+                                if let Some(filter) = filters.get_mut(i) {
+                                    *filter = rule.to_string();
+                                }
+
+                                filter_set.set_filters(filters);
+                            }
+                        ));
+                    }
+                ));
+
+                self.add_row(&row);
+
+                row
+            })
+            .collect();
+
+        imp.rows.replace(rows);
+    }
+
+    //---------------------------------------
+    // Name dialog function
+    //---------------------------------------
+    // Builds a dialog asking for a filter set name not used in model
+    pub fn name_dialog(model: &gio::ListStore, default: Option<&str>) -> (adw::AlertDialog, adw::EntryRow) {
+        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/profile_dialog.ui");
+
+        let dialog: adw::AlertDialog = builder.object("dialog")
+            .expect("Could not get object from resource");
+
+        let name_entry: adw::EntryRow = builder.object("profile_entry")
+            .expect("Could not get object from resource");
+
+        let error_label: gtk::Label = builder.object("error_label")
+            .expect("Could not get object from resource");
+
+        name_entry.connect_changed(clone!(
+            #[weak] dialog,
+            #[weak] model,
+            move |entry| {
+                let name = entry.text();
+
+                let exists = FilterSetObject::find(&model, &name).is_some();
+
+                error_label.set_label(if exists { "Filter set already exists" } else { "" });
+
+                dialog.set_response_enabled("add", !exists && !name.is_empty());
+            }
+        ));
+
+        if let Some(text) = default {
+            name_entry.set_text(text);
+        }
+
+        (dialog, name_entry)
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::sync::LazyLock;
+
+use gtk::{gio, glib};
+use gtk::prelude::*;
+
+use regex::Regex;
+
+use crate::profile_object::ProfileObject;
+use crate::filter_rule::{FilterRule, FilterType};
+
+//------------------------------------------------------------------------------
+// STRUCT: FilterTestEntry
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone)]
+pub struct FilterTestEntry {
+    pub path: String,
+    pub is_dir: bool,
+    pub included: bool,
+    pub rule: Option<String>
+}
+
+//------------------------------------------------------------------------------
+// FUNCTION: Run filter test
+//------------------------------------------------------------------------------
+pub async fn run_filter_test(profile: &ProfileObject) -> Result<Vec<FilterTestEntry>, String> {
+    // List source files with filter debug output
+    let args: Vec<String> = [profile.rsync_program()].into_iter()
+        .chain(["--dry-run", "--list-only", "--debug=filter"].into_iter().map(ToOwned::to_owned))
+        .chain(profile.options(false))
+        .chain([profile.source()])
+        .collect();
+
+    let argv: Vec<&OsStr> = args.iter()
+        .map(OsStr::new)
+        .collect();
+
+    // Force C locale for parseable output
+    let launcher = gio::SubprocessLauncher::new(gio::SubprocessFlags::STDOUT_PIPE | gio::SubprocessFlags::STDERR_PIPE);
+
+    launcher.setenv("LC_ALL", "C", true);
+
+    let process = launcher.spawnv(&argv)
+        .map_err(|error| error.to_string())?;
+
+    let (stdout, stderr) = process.communicate_future(None).await
+        .map_err(|error| error.to_string())?;
+
+    // Decode lossily (file names may not be valid UTF-8)
+    let text = |bytes: Option<glib::Bytes>| -> String {
+        bytes.map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default()
+    };
+
+    let (stdout, stderr) = (text(stdout), text(stderr));
+
+    // Exit codes 23/24 (partial transfer) still produce a usable listing
+    if !process.has_exited() || !matches!(process.exit_status(), 0 | 23 | 24) {
+        let error = stderr.trim().to_owned();
+
+        if !error.is_empty() {
+            return Err(error);
+        }
+
+        return Err(if process.has_exited() {
+            format!("Rsync exited with code {}", process.exit_status())
+        } else {
+            String::from("Rsync was terminated by a signal")
+        });
+    }
+
+    let filters: Vec<FilterRule> = profile.expanded_filters().iter()
+        .filter_map(|filter| FilterRule::parse(filter))
+        .collect();
+
+    Ok(parse_filter_test(&stdout, &filters))
+}
+
+//------------------------------------------------------------------------------
+// FUNCTION: Parse filter test
+//------------------------------------------------------------------------------
+fn parse_filter_test(output: &str, filters: &[FilterRule]) -> Vec<FilterTestEntry> {
+    static DEBUG_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^\[(?P<side>\w+)\] (?P<action>hiding|showing|protecting|risking) (?P<kind>file|directory|xattr) (?P<path>.+) because of pattern (?P<pattern>.+?)(?: \[(?P<source>.+)\])?$")
+            .expect("Failed to compile Regex")
+    });
+
+    static LIST_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"^(?P<perms>[-dlcbps][-rwxsStT]{9})\s+[\d,.]+\s+\S+\s+\S+\s(?P<path>.+)$")
+            .expect("Failed to compile Regex")
+    });
+
+    // Find profile filter rule matching rsync action, side and pattern (printed as in
+    // the rule, including anchoring "/" and trailing "/")
+    let find_rule = |action: &str, side: &str, pattern: &str, source: Option<&str>| -> String {
+        // Rules read from merge files are attributed to the merge rule
+        if let Some(source) = source {
+            let merge_rule = filters.iter()
+                .filter(|rule| rule.filter_type.is_merge())
+                .find(|rule| source.ends_with(rule.pattern.rsplit('/').next().unwrap_or_default()));
+
+            return match merge_rule {
+                Some(rule) => format!("{pattern} [{rule}]"),
+                None => format!("{pattern} [{source}]")
+            };
+        }
+
+        let types: &[FilterType] = match action {
+            "hiding" => &[FilterType::Exclude, FilterType::Hide],
+            "showing" => &[FilterType::Include, FilterType::Show],
+            "protecting" => &[FilterType::Exclude, FilterType::Protect],
+            "risking" => &[FilterType::Include, FilterType::Risk],
+            _ => &[]
+        };
+
+        // Skip rules restricted to the other side (s = sender, r = receiver)
+        let other_side = if side == "sender" { 'r' } else { 's' };
+
+        filters.iter()
+            .filter(|rule| types.contains(&rule.filter_type) && !rule.modifiers.contains(other_side))
+            .find(|rule| rule.pattern == pattern)
+            .map_or_else(|| pattern.to_owned(), ToString::to_string)
+    };
+
+    let mut shown: HashMap<String, String> = HashMap::new();
+    let mut entries: Vec<FilterTestEntry> = vec![];
+
+    for line in output.lines() {
+        if let Some(caps) = DEBUG_EXPR.captures(line) {
+            let rule = find_rule(&caps["action"], &caps["side"], &caps["pattern"], caps.name("source").map(|m| m.as_str()));
+
+            if &caps["action"] == "hiding" {
+                entries.push(FilterTestEntry {
+                    path: caps["path"].to_owned(),
+                    is_dir: &caps["kind"] == "directory",
+                    included: false,
+                    rule: Some(rule)
+                });
+            } else if &caps["action"] == "showing" {
+                shown.insert(caps["path"].to_owned(), rule);
+            }
+        } else if let Some(caps) = LIST_EXPR.captures(line) {
+            let is_link = caps["perms"].starts_with('l');
+
+            let path = if is_link {
+                caps["path"].split_once(" -> ").map_or(&caps["path"], |(path, _)| path)
+            } else {
+                &caps["path"]
+            };
+
+            entries.push(FilterTestEntry {
+                path: path.to_owned(),
+                is_dir: caps["perms"].starts_with('d'),
+                included: true,
+                rule: None
+            });
+        }
+    }
+
+    // Add include rules to included entries
+    for entry in entries.iter_mut().filter(|entry| entry.included) {
+        entry.rule = shown.remove(entry.path.trim_end_matches('/'));
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    entries
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(filters: &[&str]) -> Vec<FilterRule> {
+        filters.iter()
+            .filter_map(|filter| FilterRule::parse(filter))
+            .collect()
+    }
+
+    #[test]
+    fn rule_matches_action() {
+        let filters = rules(&["include *.log", "exclude *.log"]);
+
+        let output = "[sender] showing file keep.log because of pattern *.log\n\
+            [sender] hiding file drop.log because of pattern *.log\n\
+            -rw-r--r--          1,024 2026/10/18 09:12:44 keep.log\n";
+
+        let entries = parse_filter_test(output, &filters);
+
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].path, "drop.log");
+        assert!(!entries[0].included);
+        assert_eq!(entries[0].rule.as_deref(), Some("exclude *.log"));
+
+        assert_eq!(entries[1].path, "keep.log");
+        assert!(entries[1].included);
+        assert_eq!(entries[1].rule.as_deref(), Some("include *.log"));
+    }
+
+    #[test]
+    fn rule_matches_side_and_directory() {
+        let filters = rules(&["exclude,r cache", "exclude,s cache/", "exclude /cache", "exclude cache"]);
+
+        let output = "[sender] hiding directory var/cache because of pattern cache/\n\
+            [sender] hiding file tmp/cache because of pattern cache\n\
+            [sender] hiding directory cache because of pattern /cache\n";
+
+        let entries = parse_filter_test(output, &filters);
+
+        let rule = |path: &str| entries.iter()
+            .find(|entry| entry.path == path)
+            .and_then(|entry| entry.rule.as_deref());
+
+        assert_eq!(rule("var/cache"), Some("exclude,s cache/"));
+        assert_eq!(rule("tmp/cache"), Some("exclude cache"));
+        assert_eq!(rule("cache"), Some("exclude /cache"));
+    }
+
+    #[test]
+    fn rule_matches_anchoring_exactly() {
+        // Unanchored match is not attributed to an anchored rule
+        let filters = rules(&["exclude /cache", "exclude cache/"]);
+
+        let output = "[sender] hiding file tmp/cache because of pattern cache\n";
+
+        let entries = parse_filter_test(output, &filters);
+
+        assert_eq!(entries[0].rule.as_deref(), Some("cache"));
+    }
+
+    #[test]
+    fn rule_from_merge_file() {
+        let filters = rules(&["exclude *.log", "dir-merge,- .gitignore"]);
+
+        let output = "[sender] hiding file build/app.log because of pattern *.log [per-dir .gitignore]\n";
+
+        let entries = parse_filter_test(output, &filters);
+
+        assert_eq!(entries[0].rule.as_deref(), Some("*.log [dir-merge,- .gitignore]"));
+    }
+}
@@ -0,0 +1,203 @@
+use std::borrow::Cow;
+
+//------------------------------------------------------------------------------
+// STRUCT: Frame
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub bytes: Vec<u8>,
+    pub update: bool
+}
+
+impl Frame {
+    //---------------------------------------
+    // Text function
+    //---------------------------------------
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.bytes)
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: LineFramer
+//------------------------------------------------------------------------------
+// Splits a byte stream into lines on '\n' and '\r' across reads. Lines that
+// follow a '\r' are flagged as updates (overwrite the previous status line).
+#[derive(Default, Debug)]
+pub struct LineFramer {
+    pending: Vec<u8>,
+    update: bool
+}
+
+impl LineFramer {
+    //---------------------------------------
+    // New function
+    //---------------------------------------
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //---------------------------------------
+    // Push function
+    //---------------------------------------
+    // Returns complete frames, keeps the incomplete tail pending
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<Frame> {
+        let mut frames = vec![];
+        let mut bytes = bytes;
+
+        while let Some(end) = bytes.iter().position(|&b| b == b'\n' || b == b'\r') {
+            self.pending.extend_from_slice(&bytes[..end]);
+
+            if !self.pending.is_empty() {
+                frames.push(Frame {
+                    bytes: std::mem::take(&mut self.pending),
+                    update: self.update
+                });
+            }
+
+            self.update = bytes[end] == b'\r';
+
+            bytes = &bytes[end + 1..];
+        }
+
+        self.pending.extend_from_slice(bytes);
+
+        frames
+    }
+
+    //---------------------------------------
+    // Finish function
+    //---------------------------------------
+    // Returns the unterminated last line at end of stream
+    pub fn finish(&mut self) -> Option<Frame> {
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        Some(Frame {
+            bytes: std::mem::take(&mut self.pending),
+            update: std::mem::take(&mut self.update)
+        })
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    // Renders a fake rsync transcript to the bytes written to stdout
+    fn render(transcript: &str) -> Vec<u8> {
+        let mut bytes = vec![];
+
+        for line in transcript.lines() {
+            let (cmd, text) = line.split_once(' ').unwrap_or((line, ""));
+
+            match cmd {
+                "out" => {
+                    bytes.extend_from_slice(text.as_bytes());
+                    bytes.push(b'\n');
+                }
+                "raw" => bytes.extend_from_slice(text.replace("\\r", "\r").replace("\\n", "\n").as_bytes()),
+                _ => {}
+            }
+        }
+
+        bytes
+    }
+
+    fn transcripts(dir: &Path) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .expect("Could not read transcripts folder")
+            .flatten()
+            .map(|entry| entry.path())
+            .flat_map(|path| if path.is_dir() { transcripts(&path) } else { vec![path] })
+            .collect();
+
+        paths.sort();
+
+        paths
+    }
+
+    fn frames(chunks: &[&[u8]]) -> Vec<Frame> {
+        let mut framer = LineFramer::new();
+
+        let mut frames: Vec<Frame> = chunks.iter()
+            .flat_map(|chunk| framer.push(chunk))
+            .collect();
+
+        frames.extend(framer.finish());
+
+        frames
+    }
+
+    // Splits bytes at pseudo-random boundaries (deterministic per seed)
+    fn split(bytes: &[u8], seed: u64) -> Vec<&[u8]> {
+        let mut state = seed;
+        let mut chunks = vec![];
+        let mut rest = bytes;
+
+        while !rest.is_empty() {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+
+            let len = ((state >> 33) % 8 + 1) as usize;
+            let (chunk, tail) = rest.split_at(len.min(rest.len()));
+
+            chunks.push(chunk);
+            rest = tail;
+        }
+
+        chunks
+    }
+
+    #[test]
+    fn frames_and_update_flags() {
+        let frames = frames(&[b"a\r\nb\rc\n\rd"]);
+
+        let expected: Vec<(&[u8], bool)> = vec![(b"a", false), (b"b", false), (b"c", true), (b"d", true)];
+
+        assert_eq!(frames.iter().map(|frame| (frame.bytes.as_slice(), frame.update)).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn split_reads_match_unsplit() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/transcripts");
+
+        // Multibyte UTF-8 and CRLF line endings
+        let mut streams: Vec<Vec<u8>> = vec!["Grüße/日本語.txt\r\n 50% 1.2MB/s\r 100% 2.4MB/s\r\nemoji 🦀 done\n\rtail ü".as_bytes().to_vec()];
+
+        streams.extend(transcripts(&dir).iter()
+            .map(|path| render(&fs::read_to_string(path).expect("Could not read transcript"))));
+
+        for bytes in &streams {
+            let expected = frames(&[bytes]);
+
+            assert!(!expected.is_empty());
+
+            // Every boundary (inside UTF-8 sequences and between '\r' and '\n')
+            let bytewise: Vec<&[u8]> = bytes.chunks(1).collect();
+
+            assert_eq!(frames(&bytewise), expected);
+
+            for seed in 0..50 {
+                assert_eq!(frames(&split(bytes, seed)), expected, "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn split_inside_utf8_sequence() {
+        let bytes = "ü\r\n".as_bytes();
+
+        let frames = frames(&[&bytes[..1], &bytes[1..3], &bytes[3..]]);
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].text(), "ü");
+        assert!(!frames[0].update);
+    }
+}
@@ -1,9 +1,14 @@
 mod app;
 mod window;
+mod config;
 mod profile_object;
 mod options_page;
 mod filter_expander_row;
 mod filter_row;
+mod filter_set_row;
+mod filter_rule;
+mod filter_set_object;
+mod filter_test;
 mod advanced_page;
 mod adv_switchrow;
 mod rsync_page;
@@ -11,7 +16,14 @@ mod stats_table;
 mod output_window;
 mod output_item;
 mod output_header;
+mod output_export;
+mod output_tree;
+mod output_tree_item;
+mod output_query;
 mod rsync_process;
+mod rsync_version;
+mod line_framer;
+mod rsync_error;
 mod utils;
 
 use gtk::prelude::*;
@@ -27,7 +39,7 @@ fn main() -> glib::ExitCode {
         .expect("Failed to register resources");
 
     // Run app
-    let app = Application::new(APP_ID, gio::ApplicationFlags::default());
+    let app = Application::new(APP_ID, gio::ApplicationFlags::HANDLES_OPEN);
 
     app.run()
 }
@@ -1,16 +1,29 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::io;
 use std::fs;
 use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use adw::subclass::prelude::*;
 use adw::prelude::*;
 use gtk::{gio, glib, gdk};
-use glib::clone;
+use glib::{clone, closure_local};
 
-use serde_json::{to_string_pretty, from_str, Map as JsonMap, Value as JsonValue};
+use crate::{
+    config::{BACKUP_INTERVAL, CONFIG_VERSION, Config},
+    profile_object::{CheckMode, RecurseMode, ProfileObject},
+    filter_set_object::FilterSetObject,
+    filter_set_row::FilterSetRow,
+    utils::case
+};
 
-use crate::profile_object::{CheckMode, RecurseMode, ProfileObject};
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+const PROFILE_FILE_EXT: &str = ".syncer.json";
+const PROFILE_MIME_TYPE: &str = "application/x-syncer-profile";
+const SAVE_DELAY: u64 = 1000;
 
 //------------------------------------------------------------------------------
 // MODULE: OptionsPage
@@ -31,6 +44,9 @@ mod imp {
         #[property(get)]
         #[template_child]
         pub(super) profile_model: TemplateChild<gio::ListStore>,
+        #[property(get)]
+        #[template_child]
+        pub(super) filter_set_model: TemplateChild<gio::ListStore>,
 
         #[template_child]
         pub(super) copy_by_name_button: TemplateChild<gtk::ToggleButton>,
@@ -44,11 +60,30 @@ mod imp {
         pub(super) check_mode_combo: TemplateChild<adw::ComboRow>,
         #[template_child]
         pub(super) recurse_mode_combo: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(super) check_mode_inherited_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) check_mode_revert_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub(super) recurse_mode_inherited_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) recurse_mode_revert_button: TemplateChild<gtk::Button>,
 
         #[property(get, set, nullable)]
         profile: RefCell<Option<ProfileObject>>,
 
-        pub(super) bindings: RefCell<Option<Vec<glib::Binding>>>
+        pub(super) bindings: RefCell<Option<Vec<glib::Binding>>>,
+        pub(super) inheritance_handler: RefCell<Option<(ProfileObject, glib::SignalHandlerId)>>,
+        pub(super) profile_handlers: RefCell<Vec<(ProfileObject, glib::SignalHandlerId)>>,
+        pub(super) filter_set_handlers: RefCell<Vec<(FilterSetObject, glib::SignalHandlerId)>>,
+
+        pub(super) save_enabled: Cell<bool>,
+        pub(super) save_failed: Cell<bool>,
+        pub(super) conflict_pending: Cell<bool>,
+        pub(super) save_source: RefCell<Option<glib::SourceId>>,
+
+        pub(super) config_monitor: RefCell<Option<gio::FileMonitor>>,
+        pub(super) config_json: RefCell<String>,
     }
 
     //---------------------------------------
@@ -122,8 +157,6 @@ mod imp {
             // Delete profile action
             klass.install_action("profile.delete", None, |page, _, _| {
                 if let Some(profile) = page.profile() {
-                    let imp = page.imp();
-
                     let dialog = adw::AlertDialog::builder()
                         .heading("Delete Profile?")
                         .body(format!("Permamenently delete the \"{}\" profile.",
@@ -135,11 +168,9 @@ mod imp {
                     dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
 
                     dialog.connect_response(Some("delete"), clone!(
-                        #[weak] imp,
+                        #[weak] page,
                         move |_, _| {
-                            if let Some(pos) = imp.profile_model.find(&profile) {
-                                imp.profile_model.remove(pos);
-                            }
+                            page.remove_profile(&profile);
                         })
                     );
 
@@ -188,6 +219,27 @@ mod imp {
                 }
             });
 
+            // Import profiles action
+            klass.install_action_async("profile.import", None, async |page, _, _| {
+                let dialog = gtk::FileDialog::builder()
+                    .title("Import Profiles")
+                    .modal(true)
+                    .default_filter(&super::OptionsPage::profile_file_filter())
+                    .build();
+
+                let root = page.root()
+                    .and_downcast::<gtk::Window>();
+
+                if let Ok(file) = dialog.open_future(root.as_ref()).await {
+                    page.import_profiles(&file).await;
+                }
+            });
+
+            // Export profiles action
+            klass.install_action("profile.export", None, |page, _, _| {
+                page.export_dialog();
+            });
+
             // Delete all profiles action
             klass.install_action("profile.delete-all", None, |page, _, _| {
                 let imp = page.imp();
@@ -268,6 +320,18 @@ impl OptionsPage {
         ));
     }
 
+    //---------------------------------------
+    // Profile file filter helper function
+    //---------------------------------------
+    fn profile_file_filter() -> gtk::FileFilter {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Syncer Profiles"));
+        filter.add_mime_type(PROFILE_MIME_TYPE);
+        filter.add_suffix(PROFILE_FILE_EXT.trim_start_matches('.'));
+
+        filter
+    }
+
     //---------------------------------------
     // Setup signals
     //---------------------------------------
@@ -284,6 +348,11 @@ impl OptionsPage {
                 }
             }
 
+            // Disconnect profile inheritance changed handler
+            if let Some((profile, handler)) = imp.inheritance_handler.take() {
+                profile.disconnect(handler);
+            }
+
             if let Some(profile) = page.profile() {
                 // Set copy by name button initial state
                 let source = profile.source();
@@ -323,9 +392,111 @@ impl OptionsPage {
 
                 // Store bindings
                 imp.bindings.replace(Some(bindings));
+
+                // Profile inheritance changed signal
+                let handler = profile.connect_closure("inheritance-changed", false, closure_local!(
+                    #[weak] page,
+                    move |_: ProfileObject| {
+                        page.update_inheritance();
+                    }
+                ));
+
+                imp.inheritance_handler.replace(Some((profile, handler)));
             }
+
+            page.update_inheritance();
         });
 
+        // Check mode revert button clicked signal
+        imp.check_mode_revert_button.connect_clicked(clone!(
+            #[weak(rename_to = page)] self,
+            move |_| {
+                if let Some(profile) = page.profile() {
+                    profile.revert("check-mode");
+                }
+            }
+        ));
+
+        // Recurse mode revert button clicked signal
+        imp.recurse_mode_revert_button.connect_clicked(clone!(
+            #[weak(rename_to = page)] self,
+            move |_| {
+                if let Some(profile) = page.profile() {
+                    profile.revert("recurse-mode");
+                }
+            }
+        ));
+
+        // Profile model items changed signal
+        imp.profile_model.connect_items_changed(clone!(
+            #[weak(rename_to = page)] self,
+            move |model, pos, _, added| {
+                let imp = page.imp();
+
+                // Disconnect handlers of removed (and re-added) profiles
+                let (handlers, removed): (Vec<_>, Vec<_>) = imp.profile_handlers.take().into_iter()
+                    .partition(|(profile, _)| model.find(profile).is_some_and(|i| i < pos || i >= pos + added));
+
+                for (profile, handler) in removed {
+                    profile.disconnect(handler);
+                }
+
+                imp.profile_handlers.replace(handlers);
+
+                // Autosave config when added profiles change
+                for profile in (pos..pos + added).filter_map(|i| model.item(i).and_downcast::<ProfileObject>()) {
+                    let handler = profile.connect_notify_local(None, clone!(
+                        #[weak] page,
+                        move |_, pspec| {
+                            if pspec.name() == "parent" {
+                                page.link_profiles();
+                            }
+
+                            page.queue_save_config();
+                        }
+                    ));
+
+                    imp.profile_handlers.borrow_mut().push((profile, handler));
+                }
+
+                page.link_profiles();
+
+                page.queue_save_config();
+            }
+        ));
+
+        // Filter set model items changed signal
+        imp.filter_set_model.connect_items_changed(clone!(
+            #[weak(rename_to = page)] self,
+            move |model, pos, _, added| {
+                let imp = page.imp();
+
+                // Disconnect handlers of removed (and re-added) filter sets
+                let (handlers, removed): (Vec<_>, Vec<_>) = imp.filter_set_handlers.take().into_iter()
+                    .partition(|(filter_set, _)| model.find(filter_set).is_some_and(|i| i < pos || i >= pos + added));
+
+                for (filter_set, handler) in removed {
+                    filter_set.disconnect(handler);
+                }
+
+                imp.filter_set_handlers.replace(handlers);
+
+                // Autosave config when added filter sets are renamed or changed
+                for filter_set in (pos..pos + added).filter_map(|i| model.item(i).and_downcast::<FilterSetObject>()) {
+                    let handler = filter_set.connect_notify_local(None, clone!(
+                        #[weak] page,
+                        move |_, _| {
+                            page.queue_save_config();
+                        }
+                    ));
+
+                    imp.filter_set_handlers.borrow_mut().push((filter_set, handler));
+                }
+
+                page.queue_save_config();
+            }
+        ));
+
         // Swap paths button clicked signal
         imp.swap_paths_button.connect_clicked(clone!(
             #[weak] imp,
@@ -409,12 +580,102 @@ impl OptionsPage {
     }
 
     //---------------------------------------
-    // Profile dialog function
+    // Update inheritance function
     //---------------------------------------
-    fn profile_dialog<F>(&self, response: &str, default: Option<&str>, f: F)
-    where F: Fn(&str) + 'static {
+    fn update_inheritance(&self) {
+        let imp = self.imp();
+
+        let profile = self.profile();
+
+        let is_inherited = |nick: &str| profile.as_ref().is_some_and(|profile| profile.is_inherited(nick));
+        let is_overridden = |nick: &str| profile.as_ref().is_some_and(|profile| profile.is_overridden(nick));
+
+        imp.check_mode_inherited_label.set_visible(is_inherited("check-mode"));
+        imp.check_mode_revert_button.set_visible(is_overridden("check-mode"));
+
+        imp.recurse_mode_inherited_label.set_visible(is_inherited("recurse-mode"));
+        imp.recurse_mode_revert_button.set_visible(is_overridden("recurse-mode"));
+    }
+
+    //---------------------------------------
+    // Find profile function
+    //---------------------------------------
+    fn find_profile(&self, name: &str) -> Option<ProfileObject> {
+        self.imp().profile_model.iter::<ProfileObject>()
+            .flatten()
+            .find(|profile| profile.name().eq_ignore_ascii_case(name))
+    }
+
+    //---------------------------------------
+    // Find template function
+    //---------------------------------------
+    fn find_template(&self, profile: &ProfileObject) -> Option<ProfileObject> {
+        let imp = self.imp();
+
+        let find_parent = |profile: &ProfileObject| -> Option<ProfileObject> {
+            let name = profile.parent();
+
+            imp.profile_model.iter::<ProfileObject>()
+                .flatten()
+                .find(|parent| !name.is_empty() && parent.name() == name)
+        };
+
+        let template = find_parent(profile)?;
+
+        // Check for inheritance cycles
+        let mut ancestor = Some(template.clone());
+
+        for _ in 0..=imp.profile_model.n_items() {
+            let Some(parent) = ancestor else {
+                return Some(template);
+            };
+
+            if &parent == profile {
+                return None;
+            }
+
+            ancestor = find_parent(&parent);
+        }
+
+        None
+    }
+
+    //---------------------------------------
+    // Remove profile function
+    //---------------------------------------
+    // Clears template of profiles that inherit from removed profile
+    fn remove_profile(&self, profile: &ProfileObject) {
+        let imp = self.imp();
+
+        let Some(pos) = imp.profile_model.find(profile) else {
+            return;
+        };
+
+        imp.profile_model.remove(pos);
+
+        let name = profile.name();
+
+        for child in imp.profile_model.iter::<ProfileObject>().flatten().filter(|child| child.parent() == name) {
+            child.set_parent("");
+        }
+    }
+
+    //---------------------------------------
+    // Link profiles function
+    //---------------------------------------
+    fn link_profiles(&self) {
         let imp = self.imp();
 
+        for profile in imp.profile_model.iter::<ProfileObject>().flatten() {
+            profile.set_parent_profile(self.find_template(&profile).as_ref());
+            profile.set_filter_set_model(Some(&imp.filter_set_model));
+        }
+    }
+
+    //---------------------------------------
+    // Build profile dialog function
+    //---------------------------------------
+    fn build_profile_dialog(&self, response: &str, default: Option<&str>) -> (adw::AlertDialog, adw::EntryRow) {
         let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/profile_dialog.ui");
 
         let dialog: adw::AlertDialog = builder.object("dialog")
@@ -430,15 +691,13 @@ impl OptionsPage {
             .expect("Could not get object from resource");
 
         profile_entry.connect_changed(clone!(
-            #[weak] imp,
+            #[weak(rename_to = page)] self,
             #[weak] dialog,
             move |entry| {
                 let profile_name = entry.text();
 
-                let existing_profile = imp.profile_model.iter::<ProfileObject>()
-                    .flatten()
+                let existing_profile = page.find_profile(&profile_name)
                     .map(|profile| profile.name())
-                    .find(|name| name.eq_ignore_ascii_case(&profile_name))
                     .unwrap_or_default();
 
                 if existing_profile.is_empty() {
@@ -457,6 +716,16 @@ impl OptionsPage {
             profile_entry.set_text(&format!("{text}-1"));
         }
 
+        (dialog, profile_entry)
+    }
+
+    //---------------------------------------
+    // Profile dialog function
+    //---------------------------------------
+    fn profile_dialog<F>(&self, response: &str, default: Option<&str>, f: F)
+    where F: Fn(&str) + 'static {
+        let (dialog, profile_entry) = self.build_profile_dialog(response, default);
+
         dialog.connect_response(Some("add"), move |_, _| {
             f(&profile_entry.text());
         });
@@ -464,46 +733,754 @@ impl OptionsPage {
         dialog.present(Some(self));
     }
 
+    //---------------------------------------
+    // Error dialog function
+    //---------------------------------------
+    fn error_dialog(&self, heading: &str, error: &io::Error) {
+        let dialog = adw::AlertDialog::builder()
+            .heading(heading)
+            .body(case::capitalize_first(&error.to_string()))
+            .default_response("close")
+            .build();
+
+        dialog.add_responses(&[("close", "_Close")]);
+
+        dialog.present(Some(self));
+    }
+
+    //---------------------------------------
+    // Diagnostics dialog function
+    //---------------------------------------
+    fn diagnostics_dialog(&self, heading: &str, diagnostics: &[String]) {
+        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/diagnostics_dialog.ui");
+
+        let dialog: adw::AlertDialog = builder.object("dialog")
+            .expect("Could not get object from resource");
+
+        let label: gtk::Label = builder.object("label")
+            .expect("Could not get object from resource");
+
+        dialog.set_heading(Some(heading));
+        dialog.set_body("Some settings could not be loaded and were ignored.");
+
+        label.set_label(&diagnostics.join("\n"));
+
+        dialog.present(Some(self));
+    }
+
+    //---------------------------------------
+    // Export dialog function
+    //---------------------------------------
+    fn export_dialog(&self) {
+        let imp = self.imp();
+
+        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/export_dialog.ui");
+
+        let dialog: adw::AlertDialog = builder.object("dialog")
+            .expect("Could not get object from resource");
+
+        let profiles_group: adw::PreferencesGroup = builder.object("profiles_group")
+            .expect("Could not get object from resource");
+
+        // Add switch row for each profile (select current profile)
+        let selected = self.profile();
+
+        let rows: Vec<(ProfileObject, adw::SwitchRow)> = imp.profile_model.iter::<ProfileObject>()
+            .flatten()
+            .map(|profile| {
+                let row = adw::SwitchRow::builder()
+                    .title(profile.name())
+                    .active(selected.as_ref() == Some(&profile))
+                    .build();
+
+                row.connect_active_notify(clone!(
+                    #[weak] dialog,
+                    #[weak] profiles_group,
+                    move |_| {
+                        let any_active = (0..)
+                            .map_while(|i| profiles_group.row(i).and_downcast::<adw::SwitchRow>())
+                            .any(|row| row.is_active());
+
+                        dialog.set_response_enabled("export", any_active);
+                    }
+                ));
+
+                profiles_group.add(&row);
+
+                (profile, row)
+            })
+            .collect();
+
+        dialog.set_response_enabled("export", rows.iter().any(|(_, row)| row.is_active()));
+
+        dialog.connect_response(Some("export"), clone!(
+            #[weak(rename_to = page)] self,
+            move |_, _| {
+                let profiles: Vec<ProfileObject> = rows.iter()
+                    .filter(|(_, row)| row.is_active())
+                    .map(|(profile, _)| profile.clone())
+                    .collect();
+
+                glib::spawn_future_local(clone!(
+                    #[weak] page,
+                    async move {
+                        page.export_profiles(&profiles).await;
+                    }
+                ));
+            }
+        ));
+
+        dialog.present(Some(self));
+    }
+
+    //---------------------------------------
+    // Export profiles function
+    //---------------------------------------
+    async fn export_profiles(&self, profiles: &[ProfileObject]) {
+        let initial_name = if let [profile] = profiles {
+            format!("{}{PROFILE_FILE_EXT}", profile.name())
+        } else {
+            format!("profiles{PROFILE_FILE_EXT}")
+        };
+
+        let dialog = gtk::FileDialog::builder()
+            .title("Export Profiles")
+            .modal(true)
+            .initial_name(initial_name)
+            .default_filter(&Self::profile_file_filter())
+            .build();
+
+        let root = self.root()
+            .and_downcast::<gtk::Window>();
+
+        let Ok(file) = dialog.save_future(root.as_ref()).await else {
+            return;
+        };
+
+        let result = file.path()
+            .ok_or_else(|| io::Error::other("Invalid file path"))
+            .and_then(|path| Config::write(&path, profiles.iter().cloned(), self.referenced_filter_sets(profiles).into_iter()));
+
+        if let Err(error) = result {
+            self.error_dialog("Export Failed", &error);
+        }
+    }
+
+    //---------------------------------------
+    // Import profiles function
+    //---------------------------------------
+    pub async fn import_profiles(&self, file: &gio::File) {
+        let imp = self.imp();
+
+        let result = file.path()
+            .ok_or_else(|| io::Error::other("Invalid file path"))
+            .and_then(|path| Config::read(&path));
+
+        let config = match result {
+            Ok(config) => config,
+            Err(error) => {
+                self.error_dialog("Import Failed", &error);
+
+                return;
+            }
+        };
+
+        if !config.diagnostics.is_empty() {
+            self.diagnostics_dialog("Import Warnings", &config.diagnostics);
+        }
+
+        // Filter set changes (undone if import is cancelled)
+        let mut added_sets: Vec<FilterSetObject> = vec![];
+        let mut overwritten_sets: Vec<(FilterSetObject, Vec<String>)> = vec![];
+
+        for filter_set in config.filter_sets {
+            // Add filter set if name does not exist
+            let Some(existing) = FilterSetObject::find(&imp.filter_set_model, &filter_set.name()) else {
+                imp.filter_set_model.append(&filter_set);
+
+                added_sets.push(filter_set);
+
+                continue;
+            };
+
+            if existing.filters() == filter_set.filters() {
+                continue;
+            }
+
+            // Otherwise ask user how to resolve name conflict
+            let dialog = adw::AlertDialog::builder()
+                .heading("Filter Set Already Exists")
+                .body(format!("A filter set named \"{}\" already exists with different filters. Rename the imported filter set, overwrite the existing filter set or keep it (imported profiles will use the existing filters).",
+                    existing.name()))
+                .default_response("rename")
+                .close_response("skip")
+                .build();
+
+            dialog.add_responses(&[("skip", "_Keep Existing"), ("rename", "_Rename…"), ("overwrite", "_Overwrite")]);
+            dialog.set_response_appearance("overwrite", adw::ResponseAppearance::Destructive);
+
+            match dialog.choose_future(self).await.as_str() {
+                "rename" => {
+                    let (dialog, name_entry) = FilterSetRow::name_dialog(&imp.filter_set_model, Some(&format!("{}-1", filter_set.name())));
+
+                    dialog.set_heading(Some("Rename Filter Set"));
+                    dialog.set_response_label("add", "_Import");
+
+                    if dialog.choose_future(self).await == "add" {
+                        let old_reference = filter_set.reference();
+
+                        filter_set.set_name(name_entry.text());
+
+                        // Point imported profiles to renamed filter set
+                        for profile in &config.profiles {
+                            profile.replace_filter(&old_reference, &filter_set.reference());
+                        }
+
+                        imp.filter_set_model.append(&filter_set);
+
+                        added_sets.push(filter_set);
+                    } else {
+                        // Cancel import (profiles would use the existing filters)
+                        for filter_set in added_sets {
+                            if let Some(pos) = imp.filter_set_model.find(&filter_set) {
+                                imp.filter_set_model.remove(pos);
+                            }
+                        }
+
+                        for (filter_set, filters) in overwritten_sets {
+                            filter_set.set_filters(filters);
+                        }
+
+                        return;
+                    }
+                }
+
+                "overwrite" => {
+                    overwritten_sets.push((existing.clone(), existing.filters()));
+
+                    existing.set_filters(filter_set.filters());
+                }
+
+                _ => {}
+            }
+        }
+
+        // Confirm remote rsync commands (run on the remote host at transfer time)
+        let rsync_paths: Vec<String> = config.profiles.iter()
+            .filter(|profile| !profile.rsync_path().trim().is_empty())
+            .map(|profile| format!("<b>{}</b>\n<tt>{}</tt>",
+                glib::markup_escape_text(&profile.name()),
+                glib::markup_escape_text(profile.rsync_path().trim())))
+            .collect();
+
+        if !rsync_paths.is_empty() {
+            let dialog = adw::AlertDialog::builder()
+                .heading("Remote Rsync Commands")
+                .body(format!("The imported profiles set commands that will run on the remote host when transferring files. Only keep them if you trust this file.\n\n{}",
+                    rsync_paths.join("\n\n")))
+                .body_use_markup(true)
+                .default_response("strip")
+                .close_response("strip")
+                .build();
+
+            dialog.add_responses(&[("strip", "_Remove Commands"), ("keep", "_Keep Commands")]);
+            dialog.set_response_appearance("keep", adw::ResponseAppearance::Destructive);
+
+            if dialog.choose_future(self).await != "keep" {
+                for profile in &config.profiles {
+                    profile.set_rsync_path("");
+                }
+            }
+        }
+
+        let mut imported = None;
+
+        for profile in config.profiles {
+            // Add profile if name does not exist
+            let Some(existing) = self.find_profile(&profile.name()) else {
+                imp.profile_model.append(&profile);
+
+                imported = Some(profile);
+
+                continue;
+            };
+
+            // Otherwise ask user how to resolve name conflict
+            let dialog = adw::AlertDialog::builder()
+                .heading("Profile Already Exists")
+                .body(format!("A profile named \"{}\" already exists. Rename the imported profile, overwrite the existing profile or skip it.",
+                    existing.name()))
+                .default_response("rename")
+                .close_response("skip")
+                .build();
+
+            dialog.add_responses(&[("skip", "_Skip"), ("rename", "_Rename…"), ("overwrite", "_Overwrite")]);
+            dialog.set_response_appearance("overwrite", adw::ResponseAppearance::Destructive);
+
+            match dialog.choose_future(self).await.as_str() {
+                "rename" => {
+                    let (dialog, profile_entry) = self.build_profile_dialog("Import", Some(&profile.name()));
+
+                    if dialog.choose_future(self).await == "add" {
+                        profile.set_name(profile_entry.text());
+
+                        imp.profile_model.append(&profile);
+
+                        imported = Some(profile);
+                    }
+                }
+
+                "overwrite" => {
+                    existing.assign(&profile);
+
+                    imported = Some(existing);
+                }
+
+                _ => {}
+            }
+        }
+
+        // Select last imported profile
+        if let Some(pos) = imported.and_then(|profile| imp.profile_model.find(&profile)) {
+            imp.profile_dropdown.set_selected(pos);
+        }
+    }
+
+    //---------------------------------------
+    // Config path helper function
+    //---------------------------------------
+    fn config_path() -> io::Result<PathBuf> {
+        xdg::BaseDirectories::new()
+            .place_config_file("Syncer/config.json")
+    }
+
+    //---------------------------------------
+    // Recover config dialog function
+    //---------------------------------------
+    fn recover_config_dialog(&self, config_path: &Path, error: &str) {
+        let damaged_path = Config::suffixed_path(config_path, ".damaged");
+
+        let damaged_name = damaged_path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Configuration File Damaged")
+            .build();
+
+        // Offer to restore most recent valid backup
+        let backup = Config::latest_backup(config_path);
+
+        if let Some((backup_path, _)) = &backup {
+            let backup_name = backup_path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            dialog.set_body(&format!("The configuration file could not be loaded ({error}). Restore profiles from the most recent valid backup ({backup_name})?\n\nThe damaged file will be kept as {damaged_name}."));
+
+            dialog.add_responses(&[("discard", "_Start Empty"), ("restore", "_Restore Backup")]);
+            dialog.set_response_appearance("restore", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("restore"));
+            dialog.set_close_response("discard");
+        } else {
+            dialog.set_body(&format!("The configuration file could not be loaded ({error}) and no valid backup was found.\n\nThe damaged file will be kept as {damaged_name}."));
+
+            dialog.add_responses(&[("discard", "_Close")]);
+            dialog.set_default_response(Some("discard"));
+            dialog.set_close_response("discard");
+        }
+
+        let config_path = config_path.to_owned();
+
+        dialog.connect_response(None, clone!(
+            #[weak(rename_to = page)] self,
+            move |_, response| {
+                let imp = page.imp();
+
+                // Keep damaged config file (do not overwrite it if it cannot be moved)
+                if let Err(error) = fs::rename(&config_path, &damaged_path) && config_path.exists() {
+                    page.error_dialog("Profiles Will Not Be Saved", &io::Error::new(error.kind(),
+                        format!("The damaged configuration file could not be renamed ({error}). Changes will not be saved until it is moved or removed")));
+
+                    return;
+                }
+
+                // Restore profiles from backup
+                if response == "restore" && let Some((_, config)) = &backup {
+                    imp.filter_set_model.splice(0, 0, &config.filter_sets);
+                    imp.profile_model.splice(0, 0, &config.profiles);
+
+                    if !config.diagnostics.is_empty() {
+                        page.diagnostics_dialog("Configuration Warnings", &config.diagnostics);
+                    }
+                }
+
+                imp.save_enabled.set(true);
+
+                page.queue_save_config();
+            }
+        ));
+
+        dialog.present(Some(self));
+    }
+
     //---------------------------------------
     // Load config function
     //---------------------------------------
     pub fn load_config(&self) -> io::Result<()> {
         let imp = self.imp();
 
+        // Watch config file for external changes
+        self.monitor_config();
+
         // Load profiles from config file
-        let config_path = xdg::BaseDirectories::new()
-            .find_config_file("Syncer/config.json")
-            .ok_or_else(|| io::Error::other("Config file not found"))?;
+        let Some(config_path) = xdg::BaseDirectories::new().find_config_file("Syncer/config.json") else {
+            imp.save_enabled.set(true);
+
+            return Err(io::Error::new(io::ErrorKind::NotFound, "Config file not found"));
+        };
+
+        // Read errors (e.g. truncated or not UTF-8) are handled like parse errors
+        let result = fs::read_to_string(&config_path)
+            .and_then(|json_str| Config::parse(&json_str).map(|config| (json_str, config)));
+
+        let (json_str, mut config) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                // Show recovery dialog (saving stays disabled until resolved)
+                let error_msg = error.to_string();
+
+                glib::idle_add_local_once(clone!(
+                    #[weak(rename_to = page)] self,
+                    move || {
+                        page.recover_config_dialog(&config_path, &error_msg);
+                    }
+                ));
 
-        let json_str = fs::read_to_string(config_path)?;
+                return Err(error);
+            }
+        };
 
-        let json_object: JsonMap<String, JsonValue> = from_str(&json_str)?;
+        // Backup config file before upgrading to current version
+        if config.version < CONFIG_VERSION && let Err(error) = Config::backup(&config_path, config.version) {
+            config.diagnostics.push(format!("Could not backup config file: {error}"));
+        }
 
-        let profiles: Vec<ProfileObject> = json_object.iter()
-            .filter_map(|(name, value)| ProfileObject::from_json(name, value))
-            .collect();
+        // Add filter sets and profiles to models
+        imp.filter_set_model.splice(0, 0, &config.filter_sets);
+        imp.profile_model.splice(0, 0, &config.profiles);
+
+        imp.config_json.replace(json_str);
 
-        // Add profiles to model
-        imp.profile_model.splice(0, 0, &profiles);
+        imp.save_enabled.set(true);
+
+        // Show load diagnostics
+        if !config.diagnostics.is_empty() {
+            glib::idle_add_local_once(clone!(
+                #[weak(rename_to = page)] self,
+                move || {
+                    page.diagnostics_dialog("Configuration Warnings", &config.diagnostics);
+                }
+            ));
+        }
 
         Ok(())
     }
 
     //---------------------------------------
-    // Save config function
+    // Monitor config function
     //---------------------------------------
-    pub fn save_config(&self) -> io::Result<()> {
-        let json_object: JsonMap<String, JsonValue> = self.imp().profile_model
-            .iter::<ProfileObject>()
+    fn monitor_config(&self) {
+        let imp = self.imp();
+
+        let Ok(config_path) = Self::config_path() else {
+            return;
+        };
+
+        let Ok(monitor) = gio::File::for_path(&config_path)
+            .monitor_file(gio::FileMonitorFlags::NONE, None::<&gio::Cancellable>) else {
+                return;
+            };
+
+        monitor.connect_changed(clone!(
+            #[weak(rename_to = page)] self,
+            move |_, _, _, event| {
+                if matches!(event, gio::FileMonitorEvent::ChangesDoneHint | gio::FileMonitorEvent::Created) {
+                    page.config_changed(&config_path);
+                }
+            }
+        ));
+
+        imp.config_monitor.replace(Some(monitor));
+    }
+
+    //---------------------------------------
+    // Config changed function
+    //---------------------------------------
+    fn config_changed(&self, config_path: &Path) {
+        let imp = self.imp();
+
+        // Ignore changes while config is not loaded or conflict is pending
+        if !imp.save_enabled.get() {
+            return;
+        }
+
+        // Ignore own writes and invalid (possibly incomplete) external writes
+        let Ok(json_str) = fs::read_to_string(config_path) else {
+            return;
+        };
+
+        if *imp.config_json.borrow() == json_str {
+            return;
+        }
+
+        let Ok(config) = Config::parse(&json_str) else {
+            return;
+        };
+
+        imp.config_json.replace(json_str);
+
+        // Reload profiles if no unsaved changes
+        if imp.save_source.borrow().is_none() {
+            self.reload_config(&config);
+
+            return;
+        }
+
+        // Otherwise ask user how to resolve conflict
+        if let Some(source) = imp.save_source.take() {
+            source.remove();
+        }
+
+        self.conflict_dialog(config);
+    }
+
+    //---------------------------------------
+    // Conflict dialog function
+    //---------------------------------------
+    fn conflict_dialog(&self, config: Config) {
+        let imp = self.imp();
+
+        imp.save_enabled.set(false);
+        imp.conflict_pending.set(true);
+
+        let dialog = adw::AlertDialog::builder()
+            .heading("Configuration Changed")
+            .body("The configuration file was changed by another program, but there are unsaved changes in Syncer. Reload the configuration file and discard unsaved changes, or keep unsaved changes and overwrite the file?")
+            .default_response("reload")
+            .close_response("keep")
+            .build();
+
+        dialog.add_responses(&[("keep", "_Keep Changes"), ("reload", "_Reload")]);
+        dialog.set_response_appearance("reload", adw::ResponseAppearance::Destructive);
+
+        dialog.connect_response(None, clone!(
+            #[weak(rename_to = page)] self,
+            move |_, response| {
+                let imp = page.imp();
+
+                imp.save_enabled.set(true);
+                imp.conflict_pending.set(false);
+
+                if response == "reload" {
+                    page.reload_config(&config);
+                } else if let Err(error) = page.write_config() {
+                    page.error_dialog("Could Not Save Profiles", &error);
+                }
+            }
+        ));
+
+        dialog.present(Some(self));
+    }
+
+    //---------------------------------------
+    // Conflict pending function
+    //---------------------------------------
+    pub fn conflict_pending(&self) -> bool {
+        self.imp().conflict_pending.get()
+    }
+
+    //---------------------------------------
+    // Reload config function
+    //---------------------------------------
+    fn reload_config(&self, config: &Config) {
+        self.merge_filter_sets(&config.filter_sets);
+        self.merge_profiles(&config.profiles);
+
+        if !config.diagnostics.is_empty() {
+            self.diagnostics_dialog("Configuration Warnings", &config.diagnostics);
+        }
+    }
+
+    //---------------------------------------
+    // Merge profiles function
+    //---------------------------------------
+    fn merge_profiles(&self, profiles: &[ProfileObject]) {
+        let imp = self.imp();
+
+        // Remove deleted profiles
+        let removed: Vec<ProfileObject> = imp.profile_model.iter::<ProfileObject>()
+            .flatten()
+            .filter(|existing| !profiles.iter().any(|profile| profile.name() == existing.name()))
+            .collect();
+
+        for profile in removed {
+            self.remove_profile(&profile);
+        }
+
+        // Update existing profiles and add new profiles
+        for (pos, profile) in profiles.iter().enumerate() {
+            let existing = imp.profile_model.iter::<ProfileObject>()
+                .flatten()
+                .find(|existing| existing.name() == profile.name());
+
+            if let Some(existing) = existing {
+                existing.assign(profile);
+            } else {
+                imp.profile_model.insert((pos as u32).min(imp.profile_model.n_items()), profile);
+            }
+        }
+
+        // Do not save merged profiles back
+        if let Some(source) = imp.save_source.take() {
+            source.remove();
+        }
+    }
+
+    //---------------------------------------
+    // Merge filter sets function
+    //---------------------------------------
+    fn merge_filter_sets(&self, filter_sets: &[FilterSetObject]) {
+        let imp = self.imp();
+
+        // Remove deleted filter sets
+        let removed: Vec<FilterSetObject> = imp.filter_set_model.iter::<FilterSetObject>()
             .flatten()
-            .map(|profile| profile.to_json())
+            .filter(|existing| !filter_sets.iter().any(|filter_set| filter_set.name() == existing.name()))
             .collect();
 
-        let config_path = xdg::BaseDirectories::new()
-            .place_config_file("Syncer/config.json")?;
+        for filter_set in removed {
+            if let Some(pos) = imp.filter_set_model.find(&filter_set) {
+                imp.filter_set_model.remove(pos);
+            }
+        }
+
+        // Update existing filter sets and add new filter sets
+        for filter_set in filter_sets {
+            if let Some(existing) = FilterSetObject::find(&imp.filter_set_model, &filter_set.name()) {
+                existing.set_filters(filter_set.filters());
+            } else {
+                imp.filter_set_model.append(filter_set);
+            }
+        }
+    }
+
+    //---------------------------------------
+    // Referenced filter sets function
+    //---------------------------------------
+    fn referenced_filter_sets(&self, profiles: &[ProfileObject]) -> Vec<FilterSetObject> {
+        self.imp().filter_set_model.iter::<FilterSetObject>()
+            .flatten()
+            .filter(|filter_set| {
+                let reference = filter_set.reference();
+
+                profiles.iter().any(|profile| profile.filters().contains(&reference))
+            })
+            .collect()
+    }
+
+    //---------------------------------------
+    // Queue save config function
+    //---------------------------------------
+    fn queue_save_config(&self) {
+        let imp = self.imp();
+
+        if !imp.save_enabled.get() {
+            return;
+        }
+
+        // Restart save timer
+        if let Some(source) = imp.save_source.take() {
+            source.remove();
+        }
+
+        let source = glib::timeout_add_local_once(Duration::from_millis(SAVE_DELAY), clone!(
+            #[weak(rename_to = page)] self,
+            move || {
+                let imp = page.imp();
+
+                imp.save_source.take();
+
+                // Report autosave failure once until a save succeeds
+                match page.save_config() {
+                    Ok(()) => imp.save_failed.set(false),
+                    Err(error) => {
+                        if !imp.save_failed.replace(true) {
+                            page.error_dialog("Could Not Save Profiles", &error);
+                        }
+                    }
+                }
+            }
+        ));
+
+        imp.save_source.replace(Some(source));
+    }
+
+    //---------------------------------------
+    // Save config function
+    //---------------------------------------
+    pub fn save_config(&self) -> io::Result<()> {
+        let imp = self.imp();
+
+        // Do not overwrite config file if not loaded
+        if !imp.save_enabled.get() {
+            return Ok(());
+        }
+
+        let config_path = Self::config_path()?;
+
+        // Check for external changes since last load or save
+        let external_json = fs::read_to_string(&config_path).ok()
+            .filter(|json_str| *imp.config_json.borrow() != *json_str);
+
+        if let Some(json_str) = external_json {
+            match Config::parse(&json_str) {
+                Ok(config) => {
+                    imp.config_json.replace(json_str);
+
+                    self.conflict_dialog(config);
+
+                    return Ok(());
+                }
+                Err(_) => {
+                    // Keep damaged config file
+                    let _ = fs::rename(&config_path, Config::suffixed_path(&config_path, ".damaged"));
+                }
+            }
+        }
+
+        self.write_config()
+    }
+
+    //---------------------------------------
+    // Write config function
+    //---------------------------------------
+    // Writes config file without checking for external changes
+    fn write_config(&self) -> io::Result<()> {
+        let imp = self.imp();
+
+        let config_path = Self::config_path()?;
 
-        let json_str = to_string_pretty(&json_object)?;
+        // Rotate backups (at most once per backup interval)
+        Config::rotate_backups(&config_path, BACKUP_INTERVAL)?;
 
-        fs::write(config_path, json_str.as_bytes())
+        let json_str = Config::write(&config_path,
+            imp.profile_model.iter::<ProfileObject>().flatten(),
+            imp.filter_set_model.iter::<FilterSetObject>().flatten()
+        )?;
+
+        imp.config_json.replace(json_str);
+
+        Ok(())
     }
 }
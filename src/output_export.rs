@@ -0,0 +1,353 @@
+use serde_json::{json, Value as JsonValue};
+
+use crate::{
+    output_window::OutputObject,
+    rsync_process::RsyncMsgType
+};
+
+//------------------------------------------------------------------------------
+// STRUCT: OutputReport
+//------------------------------------------------------------------------------
+#[derive(Default, Debug)]
+pub struct OutputReport {
+    pub title: String,
+    pub status: String,
+    pub date: String,
+    pub items: Vec<OutputObject>
+}
+
+impl OutputReport {
+    //---------------------------------------
+    // Stats helper function
+    //---------------------------------------
+    fn stats(&self) -> impl Iterator<Item = &OutputObject> {
+        self.items.iter()
+            .filter(|item| item.tag == RsyncMsgType::Stat)
+    }
+
+    //---------------------------------------
+    // Messages helper function
+    //---------------------------------------
+    fn messages(&self) -> impl Iterator<Item = &OutputObject> {
+        self.items.iter()
+            .filter(|item| item.tag != RsyncMsgType::Stat)
+    }
+}
+
+//------------------------------------------------------------------------------
+// ENUM: ExportFormat
+//------------------------------------------------------------------------------
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ExportFormat {
+    Text,
+    Csv,
+    Json,
+    Html,
+}
+
+impl ExportFormat {
+    //---------------------------------------
+    // From file name function
+    //---------------------------------------
+    pub fn from_file_name(name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+
+        if name.ends_with(".csv") {
+            Self::Csv
+        } else if name.ends_with(".json") {
+            Self::Json
+        } else if name.ends_with(".html") || name.ends_with(".htm") {
+            Self::Html
+        } else {
+            Self::Text
+        }
+    }
+
+    //---------------------------------------
+    // Write function
+    //---------------------------------------
+    pub fn write(self, report: &OutputReport) -> String {
+        match self {
+            Self::Text => Self::write_text(report),
+            Self::Csv => Self::write_csv(report),
+            Self::Json => Self::write_json(report),
+            Self::Html => Self::write_html(report),
+        }
+    }
+
+    //---------------------------------------
+    // Type name helper function
+    //---------------------------------------
+    // Deletions are info messages, export them as their own type
+    fn type_name(item: &OutputObject) -> &'static str {
+        if item.tag == RsyncMsgType::Info && item.changes.starts_with("*deleting") {
+            "deleted"
+        } else {
+            item.tag.type_name()
+        }
+    }
+
+    //---------------------------------------
+    // Path helper function
+    //---------------------------------------
+    fn path(item: &OutputObject) -> &str {
+        if Self::type_name(item) == "deleted" {
+            item.path().unwrap_or(&item.msg)
+        } else {
+            &item.msg
+        }
+    }
+
+    //---------------------------------------
+    // Category helper function
+    //---------------------------------------
+    fn category(item: &OutputObject) -> &'static str {
+        item.category.map_or("", |category| category.label())
+    }
+
+    //---------------------------------------
+    // Write text helper function
+    //---------------------------------------
+    // Mimics rsync output (itemized changes followed by stats)
+    fn write_text(report: &OutputReport) -> String {
+        let mut lines: Vec<String> = report.messages()
+            .map(|item| {
+                if item.changes.is_empty() || item.changes.starts_with('*') {
+                    item.msg.clone()
+                } else {
+                    format!("{} {}", item.changes, item.msg)
+                }
+            })
+            .collect();
+
+        let stats: Vec<String> = report.stats()
+            .map(|item| item.msg.clone())
+            .collect();
+
+        if !stats.is_empty() {
+            lines.push(String::new());
+            lines.extend(stats);
+        }
+
+        if !report.status.is_empty() {
+            lines.push(String::new());
+            lines.push(report.status.clone());
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    //---------------------------------------
+    // Write CSV helper function
+    //---------------------------------------
+    fn write_csv(report: &OutputReport) -> String {
+        let field = |s: &str| -> String {
+            if s.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s.to_owned()
+            }
+        };
+
+        let mut contents = String::from("type,changes,path,size,transferred,mtime,owner,category\n");
+
+        for item in report.messages() {
+            contents.push_str(&[
+                    field(Self::type_name(item)),
+                    field(&item.changes),
+                    field(Self::path(item)),
+                    item.size.to_string(),
+                    item.transferred.to_string(),
+                    field(&item.mtime),
+                    field(&item.owner),
+                    field(Self::category(item))
+                ]
+                .join(",")
+            );
+            contents.push('\n');
+        }
+
+        contents
+    }
+
+    //---------------------------------------
+    // Write JSON helper function
+    //---------------------------------------
+    fn write_json(report: &OutputReport) -> String {
+        let items: Vec<JsonValue> = report.messages()
+            .map(|item| json!({
+                "type": Self::type_name(item),
+                "changes": item.changes,
+                "path": Self::path(item),
+                "size": item.size,
+                "transferred": item.transferred,
+                "mtime": item.mtime,
+                "owner": item.owner,
+                "category": Self::category(item)
+            }))
+            .collect();
+
+        let stats: Vec<&str> = report.stats()
+            .map(|item| item.msg.as_str())
+            .collect();
+
+        let json_value = json!({
+            "title": report.title,
+            "date": report.date,
+            "status": report.status,
+            "items": items,
+            "stats": stats
+        });
+
+        serde_json::to_string_pretty(&json_value).unwrap_or_default()
+    }
+
+    //---------------------------------------
+    // Write HTML helper function
+    //---------------------------------------
+    fn write_html(report: &OutputReport) -> String {
+        let escape = |s: &str| -> String {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+
+        let stats_rows: String = report.stats()
+            .filter_map(|item| item.msg.split_once(':'))
+            .map(|(name, value)| format!("<tr><th>{}</th><td>{}</td></tr>\n", escape(name.trim()), escape(value.trim())))
+            .collect();
+
+        let item_rows: String = report.messages()
+            .map(|item| format!("<tr class=\"{}\"><td>{}</td><td class=\"mono\">{}</td><td class=\"mono\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                Self::type_name(item),
+                Self::type_name(item),
+                escape(&item.changes),
+                escape(Self::path(item)),
+                item.size,
+                item.transferred,
+                escape(&item.mtime),
+                escape(&item.owner),
+                escape(Self::category(item))
+            ))
+            .collect();
+
+        format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; color: #222; }}
+h1 {{ font-size: 1.5em; margin-bottom: 0.2em; }}
+p.date {{ color: #777; margin-top: 0; }}
+p.status {{ font-weight: bold; }}
+table {{ border-collapse: collapse; margin-bottom: 2em; }}
+th, td {{ text-align: left; padding: 0.2em 0.8em; border-bottom: 1px solid #ddd; }}
+td.mono {{ font-family: monospace; }}
+td.num {{ text-align: right; }}
+tr.error td {{ color: #c01c28; }}
+tr.deleted td {{ color: #777; text-decoration: line-through; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p class="date">{date}</p>
+<p class="status">{status}</p>
+<h2>Statistics</h2>
+<table>
+{stats_rows}</table>
+<h2>Output</h2>
+<table>
+<tr><th>Type</th><th>Changes</th><th>Path</th><th>Size</th><th>Transferred</th><th>Modified</th><th>Owner</th><th>Category</th></tr>
+{item_rows}</table>
+</body>
+</html>
+"#,
+            title = escape(&report.title),
+            date = escape(&report.date),
+            status = escape(&report.status)
+        )
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(items: Vec<OutputObject>) -> OutputReport {
+        OutputReport {
+            title: String::from("Backup <home> & more"),
+            status: String::from("Success"),
+            date: String::from("2026-10-19 09:00"),
+            items
+        }
+    }
+
+    fn file(changes: &str, msg: &str) -> OutputObject {
+        OutputObject {
+            changes: changes.to_owned(),
+            ..OutputObject::new(RsyncMsgType::f, msg)
+        }
+    }
+
+    fn deletion(path: &str) -> OutputObject {
+        OutputObject {
+            changes: String::from("*deleting"),
+            ..OutputObject::new(RsyncMsgType::Info, &format!("deleting {path}"))
+        }
+    }
+
+    #[test]
+    fn csv_quoting() {
+        let contents = ExportFormat::Csv.write(&report(vec![
+            file(">f+++++++++", "plain.txt"),
+            file(">f+++++++++", "a,b.txt"),
+            file(">f.st......", "say \"hi\".txt"),
+            file(">f+++++++++", "line\nbreak.txt"),
+        ]));
+
+        let lines: Vec<&str> = contents.lines().collect();
+
+        assert_eq!(lines[0], "type,changes,path,size,transferred,mtime,owner,category");
+        assert_eq!(lines[1], "file,>f+++++++++,plain.txt,0,0,,,");
+        assert_eq!(lines[2], "file,>f+++++++++,\"a,b.txt\",0,0,,,");
+        assert_eq!(lines[3], "file,>f.st......,\"say \"\"hi\"\".txt\",0,0,,,");
+        assert!(contents.contains("file,>f+++++++++,\"line\nbreak.txt\",0,0,,,\n"));
+    }
+
+    #[test]
+    fn csv_deletion_type() {
+        let contents = ExportFormat::Csv.write(&report(vec![deletion("old, file.txt")]));
+
+        assert_eq!(contents.lines().nth(1), Some("deleted,*deleting,\"old, file.txt\",0,0,,,"));
+    }
+
+    #[test]
+    fn json_deletion_type() {
+        let contents = ExportFormat::Json.write(&report(vec![deletion("old.txt"), file(">f+++++++++", "new.txt")]));
+
+        let json_value: JsonValue = serde_json::from_str(&contents)
+            .expect("Could not parse exported JSON");
+
+        assert_eq!(json_value["items"][0]["type"], "deleted");
+        assert_eq!(json_value["items"][0]["path"], "old.txt");
+        assert_eq!(json_value["items"][1]["type"], "file");
+    }
+
+    #[test]
+    fn html_escaping() {
+        let contents = ExportFormat::Html.write(&report(vec![
+            file(">f+++++++++", "<script>&amp;.txt"),
+            deletion("a&b.txt"),
+        ]));
+
+        assert!(contents.contains("<title>Backup &lt;home&gt; &amp; more</title>"));
+        assert!(contents.contains("<td class=\"mono\">&lt;script&gt;&amp;amp;.txt</td>"));
+        assert!(contents.contains("<tr class=\"deleted\"><td>deleted</td><td class=\"mono\">*deleting</td><td class=\"mono\">a&amp;b.txt</td>"));
+        assert!(!contents.contains("<script>"));
+    }
+}
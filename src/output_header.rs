@@ -59,9 +59,15 @@ impl OutputHeader {
     // Bind function
     //---------------------------------------
     pub fn bind(&self, obj: &OutputObject) {
+        if let Some(attempt) = obj.attempt {
+            self.imp().label.set_label(&format!("Attempt {attempt}"));
+
+            return;
+        }
+
         self.imp().label.set_label(
             match obj.tag {
-                RsyncMsgType::Error => "Errors",
+                RsyncMsgType::Error => obj.category.map_or("Errors", |category| category.label()),
                 RsyncMsgType::Stat => "Statistics",
                 _ => "Output",
             }
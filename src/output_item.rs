@@ -1,12 +1,23 @@
+use std::cell::{RefCell, OnceCell};
+
 use gtk::subclass::prelude::*;
-use gtk::prelude::WidgetExt;
-use gtk::glib;
+use gtk::prelude::*;
+use gtk::{gio, glib, gdk, pango};
+use glib::clone;
 
 use crate::{
-    output_window::OutputObject,
-    rsync_process::RsyncMsgType
+    output_window::{OutputObject, OutputWindow},
+    output_tree::ChangeKind,
+    rsync_process::RsyncMsgType,
+    rsync_error::RsyncErrorCategory,
+    utils::{convert, case}
 };
 
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+const COMPARE_ATTRIBUTES: &str = "standard::size,time::modified,unix::mode,owner::user,owner::group";
+
 //------------------------------------------------------------------------------
 // MODULE: OutputItem
 //------------------------------------------------------------------------------
@@ -23,6 +34,15 @@ mod imp {
         pub(super) image: TemplateChild<gtk::Image>,
         #[template_child]
         pub(super) label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) size_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) menu: TemplateChild<gio::MenuModel>,
+
+        pub(super) popover: OnceCell<gtk::PopoverMenu>,
+
+        pub(super) path: RefCell<Option<String>>,
+        pub(super) raw_path: RefCell<Vec<u8>>,
     }
 
     //---------------------------------------
@@ -36,6 +56,9 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             klass.bind_template();
+
+            Self::install_actions(klass);
+            Self::bind_shortcuts(klass);
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -43,9 +66,82 @@ mod imp {
         }
     }
 
-    impl ObjectImpl for OutputItem {}
+    impl ObjectImpl for OutputItem {
+        //---------------------------------------
+        // Constructor
+        //---------------------------------------
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.obj().setup_widgets();
+        }
+
+        //---------------------------------------
+        // Dispose function
+        //---------------------------------------
+        fn dispose(&self) {
+            if let Some(popover) = self.popover.get() {
+                popover.unparent();
+            }
+        }
+    }
+
     impl WidgetImpl for OutputItem {}
     impl BoxImpl for OutputItem {}
+
+    impl OutputItem {
+        //---------------------------------------
+        // Install actions
+        //---------------------------------------
+        fn install_actions(klass: &mut <Self as ObjectSubclass>::Class) {
+            // Open file action
+            klass.install_action_async("item.open", None, async |item, _, _| {
+                item.open_file().await;
+            });
+
+            // Reveal file action
+            klass.install_action_async("item.reveal", None, async |item, _, _| {
+                item.reveal_file().await;
+            });
+
+            // Copy source path action
+            klass.install_action("item.copy-source", None, |item, _, _| {
+                if let Some((source, _)) = item.locations() {
+                    item.clipboard().set_text(&source);
+                }
+            });
+
+            // Copy destination path action
+            klass.install_action("item.copy-destination", None, |item, _, _| {
+                if let Some((_, destination)) = item.locations() {
+                    item.clipboard().set_text(&destination);
+                }
+            });
+
+            // Compare files action
+            klass.install_action_async("item.compare", None, async |item, _, _| {
+                item.compare_dialog().await;
+            });
+        }
+
+        //---------------------------------------
+        // Bind shortcuts
+        //---------------------------------------
+        fn bind_shortcuts(klass: &mut <Self as ObjectSubclass>::Class) {
+            // Context menu key binding
+            klass.add_binding(gdk::Key::Menu, gdk::ModifierType::NO_MODIFIER_MASK, |item| {
+                item.popup_menu(None);
+
+                glib::Propagation::Stop
+            });
+
+            klass.add_binding(gdk::Key::F10, gdk::ModifierType::SHIFT_MASK, |item| {
+                item.popup_menu(None);
+
+                glib::Propagation::Stop
+            });
+        }
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -58,18 +154,302 @@ glib::wrapper! {
 }
 
 impl OutputItem {
+    //---------------------------------------
+    // Setup widgets
+    //---------------------------------------
+    fn setup_widgets(&self) {
+        let imp = self.imp();
+
+        // Create context menu
+        let popover = gtk::PopoverMenu::from_model(Some(&imp.menu.get()));
+        popover.set_parent(self);
+        popover.set_has_arrow(false);
+        popover.set_halign(gtk::Align::Start);
+
+        imp.popover.set(popover)
+            .expect("Could not set context menu");
+
+        // Right click gesture
+        let gesture = gtk::GestureClick::builder()
+            .button(gdk::BUTTON_SECONDARY)
+            .build();
+
+        gesture.connect_pressed(clone!(
+            #[weak(rename_to = item)] self,
+            move |gesture, _, x, y| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+
+                item.popup_menu(Some((x, y)));
+            }
+        ));
+
+        self.add_controller(gesture);
+
+        // Long press gesture (touch)
+        let gesture = gtk::GestureLongPress::builder()
+            .touch_only(true)
+            .build();
+
+        gesture.connect_pressed(clone!(
+            #[weak(rename_to = item)] self,
+            move |gesture, x, y| {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+
+                item.popup_menu(Some((x, y)));
+            }
+        ));
+
+        self.add_controller(gesture);
+    }
+
+    //---------------------------------------
+    // Popup menu helper function
+    //---------------------------------------
+    fn popup_menu(&self, position: Option<(f64, f64)>) {
+        let imp = self.imp();
+
+        if imp.path.borrow().is_none() {
+            return;
+        }
+
+        let Some(popover) = imp.popover.get() else {
+            return;
+        };
+
+        let (x, y) = position.unwrap_or((0.0, f64::from(self.height())));
+
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+        popover.popup();
+    }
+
+    //---------------------------------------
+    // Locations helper function
+    //---------------------------------------
+    // Returns full source and destination locations of item
+    fn locations(&self) -> Option<(String, String)> {
+        let path = self.imp().path.borrow().clone()?;
+
+        let window = self.root()
+            .and_downcast::<OutputWindow>()?;
+
+        Some((window.source_location(&path), window.destination_location(&path)))
+    }
+
+    //---------------------------------------
+    // Local files helper function
+    //---------------------------------------
+    // Returns local source and destination files of item (from raw path bytes)
+    fn local_files(&self) -> (Option<gio::File>, Option<gio::File>) {
+        let raw_path = self.imp().raw_path.borrow();
+
+        let Some(window) = self.root().and_downcast::<OutputWindow>() else {
+            return (None, None);
+        };
+
+        (window.source_file(&raw_path), window.destination_file(&raw_path))
+    }
+
+    //---------------------------------------
+    // Local file helper function
+    //---------------------------------------
+    // Returns destination copy if it exists, otherwise source copy
+    fn local_file(&self) -> Option<gio::File> {
+        let (source, destination) = self.local_files();
+
+        [destination, source].into_iter()
+            .flatten()
+            .find(|file| file.query_exists(None::<&gio::Cancellable>))
+    }
+
+    //---------------------------------------
+    // Open file function
+    //---------------------------------------
+    async fn open_file(&self) {
+        let Some(file) = self.local_file() else {
+            self.error_dialog("Error Opening File", "The file does not exist or is on a remote host");
+            return;
+        };
+
+        let window = self.root()
+            .and_downcast::<gtk::Window>();
+
+        if let Err(error) = gtk::FileLauncher::new(Some(&file)).launch_future(window.as_ref()).await {
+            self.error_dialog("Error Opening File", &case::capitalize_first(&error.to_string()));
+        }
+    }
+
+    //---------------------------------------
+    // Reveal file function
+    //---------------------------------------
+    // Uses the FileManager1 interface (or portal), falls back to opening the parent folder
+    async fn reveal_file(&self) {
+        let Some(file) = self.local_file() else {
+            self.error_dialog("Error Showing File", "The file does not exist or is on a remote host");
+            return;
+        };
+
+        let window = self.root()
+            .and_downcast::<gtk::Window>();
+
+        if gtk::FileLauncher::new(Some(&file)).open_containing_folder_future(window.as_ref()).await.is_ok() {
+            return;
+        }
+
+        let Some(parent) = file.parent() else {
+            return;
+        };
+
+        if let Err(error) = gio::AppInfo::launch_default_for_uri_future(&parent.uri(), None::<&gio::AppLaunchContext>).await {
+            self.error_dialog("Error Showing File", &case::capitalize_first(&error.to_string()));
+        }
+    }
+
+    //---------------------------------------
+    // Compare dialog function
+    //---------------------------------------
+    async fn compare_dialog(&self) {
+        let (source, destination) = self.local_files();
+
+        // Query file info of source and destination
+        let query = async |file: Option<gio::File>| -> Option<gio::FileInfo> {
+            file?
+                .query_info_future(COMPARE_ATTRIBUTES, gio::FileQueryInfoFlags::NOFOLLOW_SYMLINKS, glib::Priority::DEFAULT)
+                .await
+                .ok()
+        };
+
+        let source_info = query(source).await;
+        let destination_info = query(destination).await;
+
+        let builder = gtk::Builder::from_resource("/com/github/Syncer/ui/builder/compare_dialog.ui");
+
+        let dialog: adw::AlertDialog = builder.object("dialog")
+            .expect("Could not get object from resource");
+
+        let grid: gtk::Grid = builder.object("grid")
+            .expect("Could not get object from resource");
+
+        dialog.set_body(self.imp().path.borrow().as_deref().unwrap_or_default());
+
+        // Fill comparison grid
+        let rows: [(&str, fn(&gio::FileInfo) -> Option<String>); 4] = [
+            ("Size", |info| Some(format!("{}B", convert::num_to_size(info.size() as f64)))),
+            ("Modified", |info| info.modification_date_time()?.to_local().ok()?.format("%x %X").ok().map(String::from)),
+            ("Mode", |info| info.has_attribute("unix::mode").then(|| format!("{:04o}", info.attribute_uint32("unix::mode") & 0o7777))),
+            ("Owner", |info| Some(format!("{}:{}", info.attribute_string("owner::user")?, info.attribute_string("owner::group")?))),
+        ];
+
+        for (i, (name, value)) in rows.iter().enumerate() {
+            let row = i as i32 + 1;
+
+            let source_value = source_info.as_ref().and_then(*value);
+            let destination_value = destination_info.as_ref().and_then(*value);
+
+            let differs = source_value != destination_value;
+
+            let name_label = gtk::Label::builder()
+                .label(*name)
+                .xalign(0.0)
+                .css_classes(["heading"])
+                .build();
+
+            grid.attach(&name_label, 0, row, 1, 1);
+
+            for (column, value) in [(1, source_value), (2, destination_value)] {
+                let label = gtk::Label::builder()
+                    .label(value.as_deref().unwrap_or("—"))
+                    .xalign(0.0)
+                    .selectable(true)
+                    .build();
+
+                if differs {
+                    label.add_css_class("warning");
+                }
+
+                grid.attach(&label, column, row, 1, 1);
+            }
+        }
+
+        dialog.present(Some(self));
+    }
+
+    //---------------------------------------
+    // Error dialog helper function
+    //---------------------------------------
+    fn error_dialog(&self, heading: &str, body: &str) {
+        let dialog = adw::AlertDialog::builder()
+            .heading(heading)
+            .body(body)
+            .default_response("close")
+            .build();
+
+        dialog.add_responses(&[("close", "_Close")]);
+
+        dialog.present(Some(self));
+    }
+
     //---------------------------------------
     // Bind function
     //---------------------------------------
-    pub fn bind(&self, obj: &OutputObject) {
+    pub fn bind(&self, obj: &OutputObject, highlights: &[(usize, usize)]) {
         let imp = self.imp();
 
+        // Store path for context menu actions
+        let path = obj.path().map(ToOwned::to_owned);
+
+        self.action_set_enabled("item.compare", path.is_some() && ChangeKind::from_changes(&obj.changes) == ChangeKind::Updated);
+
+        imp.path.replace(path);
+        imp.raw_path.replace(obj.raw_path.clone());
+
         let msg = &obj.msg;
 
         imp.label.set_label(msg);
 
+        // Highlight search matches
+        let attrs = pango::AttrList::new();
+
+        for &(start, end) in highlights {
+            let mut weight = pango::AttrInt::new_weight(pango::Weight::Bold);
+            weight.set_start_index(start as u32);
+            weight.set_end_index(end as u32);
+
+            attrs.insert(weight);
+
+            let mut underline = pango::AttrInt::new_underline(pango::Underline::Single);
+            underline.set_start_index(start as u32);
+            underline.set_end_index(end as u32);
+
+            attrs.insert(underline);
+        }
+
+        imp.label.set_attributes(Some(&attrs));
+
+        // Show file size and details
+        let show_size = obj.tag == RsyncMsgType::f && obj.size > 0;
+
+        imp.size_label.set_label(&if show_size { format!("{}B", convert::num_to_size(obj.size as f64)) } else { String::new() });
+        imp.size_label.set_visible(show_size);
+
+        let details: Vec<String> = [
+            (obj.transferred > 0).then(|| format!("Transferred: {}B", convert::num_to_size(obj.transferred as f64))),
+            (!obj.mtime.is_empty()).then(|| format!("Modified: {}", obj.mtime)),
+            (!obj.owner.is_empty()).then(|| format!("Owner: {}", obj.owner)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let tooltip = details.join("\n");
+
+        self.set_tooltip_text((!tooltip.is_empty()).then_some(tooltip.as_str()));
+
+        let vanished = obj.category == Some(RsyncErrorCategory::Vanished);
+
         self.set_css_classes(
-            if obj.tag == RsyncMsgType::Error {
+            if vanished {
+                &["warning"]
+            } else if obj.tag == RsyncMsgType::Error {
                 &["error"]
             } else {
                 &[]
@@ -77,6 +457,7 @@ impl OutputItem {
         );
 
         imp.image.set_icon_name(match obj.tag {
+            RsyncMsgType::Error if vanished => Some("dialog-warning-symbolic"),
             RsyncMsgType::Error => Some("rsync-error-symbolic"),
             RsyncMsgType::Stat => Some("stats-symbolic"),
             RsyncMsgType::Info => {
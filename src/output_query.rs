@@ -0,0 +1,485 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    output_window::OutputObject,
+    output_tree::ChangeKind,
+    rsync_process::RsyncMsgType
+};
+
+//------------------------------------------------------------------------------
+// ENUM: QueryTerm
+//------------------------------------------------------------------------------
+#[derive(Debug, Clone)]
+enum QueryTerm {
+    Text(String),
+    Regex(Regex),
+    Glob(Regex, bool),
+    Type(Vec<RsyncMsgType>),
+    Change(ChangeKind),
+    Path(String),
+    PathGlob(Regex),
+}
+
+impl QueryTerm {
+    //---------------------------------------
+    // Parse function
+    //---------------------------------------
+    fn parse(token: &str) -> Result<Self, String> {
+        // Regex term
+        if token.len() >= 2 && token.starts_with('/') && token.ends_with('/') {
+            let pattern = &token[1..token.len() - 1];
+
+            return RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map(Self::Regex)
+                .map_err(|_| format!("Invalid regular expression \"{pattern}\""));
+        }
+
+        // Field predicates
+        if let Some((field, value)) = token.split_once(':') {
+            match field {
+                "type" => {
+                    let tags = match value {
+                        "file" | "files" => vec![RsyncMsgType::f],
+                        "dir" | "dirs" => vec![RsyncMsgType::d],
+                        "link" | "links" => vec![RsyncMsgType::L],
+                        "device" | "devices" => vec![RsyncMsgType::D],
+                        "special" | "specials" => vec![RsyncMsgType::D, RsyncMsgType::S],
+                        "info" => vec![RsyncMsgType::Info],
+                        "error" | "errors" => vec![RsyncMsgType::Error],
+                        "stat" | "stats" => vec![RsyncMsgType::Stat],
+                        _ => return Err(format!("Unknown type \"{value}\""))
+                    };
+
+                    return Ok(Self::Type(tags));
+                }
+                "change" => {
+                    let kind = match value {
+                        "new" => ChangeKind::New,
+                        "updated" | "update" => ChangeKind::Updated,
+                        "deleted" | "delete" => ChangeKind::Deleted,
+                        "unchanged" => ChangeKind::Unchanged,
+                        _ => return Err(format!("Unknown change \"{value}\""))
+                    };
+
+                    return Ok(Self::Change(kind));
+                }
+                "path" => {
+                    if Self::is_glob(value) {
+                        return Ok(Self::PathGlob(Self::glob_regex(value)?));
+                    }
+
+                    return Ok(Self::Path(value.to_ascii_lowercase()));
+                }
+                _ => {}
+            }
+        }
+
+        // Glob term (match file name unless pattern contains a slash)
+        if Self::is_glob(token) {
+            return Ok(Self::Glob(Self::glob_regex(token)?, token.contains('/')));
+        }
+
+        Ok(Self::Text(token.to_ascii_lowercase()))
+    }
+
+    //---------------------------------------
+    // Is glob helper function
+    //---------------------------------------
+    fn is_glob(s: &str) -> bool {
+        s.contains(['*', '?', '['])
+    }
+
+    //---------------------------------------
+    // Glob regex helper function
+    //---------------------------------------
+    fn glob_regex(glob: &str) -> Result<Regex, String> {
+        let mut pattern = String::from("^");
+        let mut chars = glob.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    pattern.push_str(".*");
+                }
+                '*' => pattern.push_str("[^/]*"),
+                '?' => pattern.push_str("[^/]"),
+                // Unterminated brackets match literally
+                '[' if !chars.clone().any(|c| c == ']') => pattern.push_str("\\["),
+                '[' => {
+                    pattern.push('[');
+
+                    if chars.peek() == Some(&'!') {
+                        chars.next();
+                        pattern.push('^');
+                    }
+
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+
+                        if c == '\\' || c == '[' {
+                            pattern.push('\\');
+                        }
+
+                        pattern.push(c);
+                    }
+
+                    pattern.push(']');
+                }
+                _ => pattern.push_str(&regex::escape(&c.to_string()))
+            }
+        }
+
+        pattern.push_str("/?$");
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|_| format!("Invalid pattern \"{glob}\""))
+    }
+
+    //---------------------------------------
+    // Glob target helper function
+    //---------------------------------------
+    // Returns the part of the path a glob is matched against
+    fn glob_target(path: &str, full_path: bool) -> &str {
+        if full_path {
+            return path;
+        }
+
+        let start = path.trim_end_matches('/')
+            .rfind('/')
+            .map_or(0, |i| i + 1);
+
+        &path[start..]
+    }
+
+    //---------------------------------------
+    // Matches function
+    //---------------------------------------
+    fn matches(&self, obj: &OutputObject) -> bool {
+        match self {
+            Self::Text(text) => obj.msg.to_ascii_lowercase().contains(text),
+            Self::Regex(regex) => regex.is_match(&obj.msg),
+            Self::Glob(regex, full_path) => {
+                let path = obj.path().unwrap_or(&obj.msg);
+
+                regex.is_match(Self::glob_target(path, *full_path))
+            }
+            Self::Type(tags) => tags.contains(&obj.tag),
+            Self::Change(kind) => obj.path().is_some() && ChangeKind::from_changes(&obj.changes) == *kind,
+            Self::Path(prefix) => obj.path().is_some_and(|path| {
+                let path = path.to_ascii_lowercase();
+
+                path.starts_with(prefix) || path.contains(&format!("/{prefix}"))
+            }),
+            Self::PathGlob(regex) => obj.path().is_some_and(|path| regex.is_match(path)),
+        }
+    }
+
+    //---------------------------------------
+    // Highlights function
+    //---------------------------------------
+    // Returns byte ranges of matched text in message
+    fn highlights(&self, obj: &OutputObject) -> Vec<(usize, usize)> {
+        let msg = &obj.msg;
+
+        // Offset of path in message (deleted entries are prefixed)
+        let path_offset = |path: &str| path.as_ptr() as usize - msg.as_ptr() as usize;
+
+        match self {
+            Self::Text(text) if !text.is_empty() => {
+                msg.to_ascii_lowercase()
+                    .match_indices(text.as_str())
+                    .map(|(start, s)| (start, start + s.len()))
+                    .collect()
+            }
+            Self::Regex(regex) => {
+                regex.find_iter(msg)
+                    .filter(|m| !m.is_empty())
+                    .map(|m| (m.start(), m.end()))
+                    .collect()
+            }
+            Self::Glob(_, full_path) => {
+                obj.path()
+                    .map(|path| {
+                        let target = Self::glob_target(path, *full_path);
+                        let start = path_offset(target);
+
+                        vec![(start, start + target.len())]
+                    })
+                    .unwrap_or_default()
+            }
+            Self::Path(prefix) if !prefix.is_empty() => {
+                obj.path()
+                    .and_then(|path| {
+                        let start = path_offset(path) + path.to_ascii_lowercase().find(prefix.as_str())?;
+
+                        Some(vec![(start, start + prefix.len())])
+                    })
+                    .unwrap_or_default()
+            }
+            _ => vec![]
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: QueryClause
+//------------------------------------------------------------------------------
+#[derive(Debug, Clone)]
+struct QueryClause {
+    term: QueryTerm,
+    negate: bool
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: OutputQuery
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone)]
+pub struct OutputQuery {
+    clauses: Vec<QueryClause>
+}
+
+impl OutputQuery {
+    //---------------------------------------
+    // Parse function
+    //---------------------------------------
+    // Terms are combined with AND, prefix with NOT or - to negate
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut clauses = vec![];
+        let mut negate = false;
+
+        for token in Self::tokenize(query) {
+            match token.as_str() {
+                "AND" => continue,
+                "NOT" => {
+                    negate = !negate;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let (token, minus) = match token.strip_prefix('-') {
+                Some(stripped) if !stripped.is_empty() => (stripped, true),
+                _ => (token.as_str(), false)
+            };
+
+            clauses.push(QueryClause {
+                term: QueryTerm::parse(token)?,
+                negate: negate != minus
+            });
+
+            negate = false;
+        }
+
+        Ok(Self { clauses })
+    }
+
+    //---------------------------------------
+    // Tokenize helper function
+    //---------------------------------------
+    fn tokenize(query: &str) -> Vec<String> {
+        let mut tokens = vec![];
+        let mut chars = query.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+
+            let mut token = String::new();
+
+            if c == '-' {
+                token.push(c);
+                chars.next();
+            }
+
+            // Regex (may contain spaces, ends at unescaped slash)
+            if chars.peek() == Some(&'/') {
+                token.push('/');
+                chars.next();
+
+                let mut escaped = false;
+
+                for c in chars.by_ref() {
+                    token.push(c);
+
+                    if c == '/' && !escaped {
+                        break;
+                    }
+
+                    escaped = c == '\\' && !escaped;
+                }
+            }
+
+            // Word (may contain quoted spaces)
+            let mut quoted = false;
+
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() && !quoted {
+                    break;
+                }
+
+                chars.next();
+
+                if c == '"' {
+                    quoted = !quoted;
+                } else {
+                    token.push(c);
+                }
+            }
+
+            tokens.push(token);
+        }
+
+        tokens
+    }
+
+    //---------------------------------------
+    // Is empty function
+    //---------------------------------------
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    //---------------------------------------
+    // Matches function
+    //---------------------------------------
+    pub fn matches(&self, obj: &OutputObject) -> bool {
+        self.clauses.iter()
+            .all(|clause| clause.term.matches(obj) != clause.negate)
+    }
+
+    //---------------------------------------
+    // Highlights function
+    //---------------------------------------
+    pub fn highlights(&self, obj: &OutputObject) -> Vec<(usize, usize)> {
+        self.clauses.iter()
+            .filter(|clause| !clause.negate)
+            .flat_map(|clause| clause.term.highlights(obj))
+            .collect()
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(tag: RsyncMsgType, changes: &str, msg: &str) -> OutputObject {
+        OutputObject {
+            changes: changes.to_owned(),
+            ..OutputObject::new(tag, msg)
+        }
+    }
+
+    fn highlights(query: &str, obj: &OutputObject) -> Vec<(usize, usize)> {
+        OutputQuery::parse(query)
+            .unwrap_or_else(|error| panic!("{error}"))
+            .highlights(obj)
+    }
+
+    #[test]
+    fn tokenize_quotes_and_regexes() {
+        let cases: [(&str, &[&str]); 7] = [
+            ("foo  bar", &["foo", "bar"]),
+            ("\"my file\" foo", &["my file", "foo"]),
+            ("path:\"My Documents/\"", &["path:My Documents/"]),
+            ("/a b/ c", &["/a b/", "c"]),
+            ("/a\\/b/ x", &["/a\\/b/", "x"]),
+            ("-foo NOT bar", &["-foo", "NOT", "bar"]),
+            ("-/tmp dir/ -\"a b\"", &["-/tmp dir/", "-a b"]),
+        ];
+
+        for (query, tokens) in cases {
+            assert_eq!(OutputQuery::tokenize(query), tokens, "{query}");
+        }
+    }
+
+    #[test]
+    fn parse_negation() {
+        let log = item(RsyncMsgType::f, ">f+++++++++", "logs/app.log");
+        let tmp = item(RsyncMsgType::f, ">f+++++++++", "cache/a.tmp");
+
+        let cases = [
+            ("-*.tmp", true, false),
+            ("NOT *.tmp", true, false),
+            ("NOT -*.tmp", false, true),
+            ("type:file AND -logs/", false, true),
+            ("/a b|\\.tmp$/", false, true),
+            ("/logs\\/app/", true, false),
+        ];
+
+        for (query, log_matches, tmp_matches) in cases {
+            let query_obj = OutputQuery::parse(query)
+                .unwrap_or_else(|error| panic!("{error}"));
+
+            assert_eq!(query_obj.matches(&log), log_matches, "{query}");
+            assert_eq!(query_obj.matches(&tmp), tmp_matches, "{query}");
+        }
+
+        // Lone minus is a search term
+        assert!(OutputQuery::parse("-").is_ok_and(|query| query.matches(&item(RsyncMsgType::Info, "", "a - b"))));
+
+        assert!(OutputQuery::parse("/(/").is_err());
+        assert!(OutputQuery::parse("type:bogus").is_err());
+    }
+
+    #[test]
+    fn glob_regex_patterns() {
+        let cases = [
+            ("**/*.rs", "src/ui/main.rs", true),
+            ("**", "a/b/c", true),
+            ("src/*.rs", "src/ui/main.rs", false),
+            ("*.rs", "main.rs", true),
+            ("file?.txt", "file1.txt", true),
+            ("[!a]*.txt", "b.txt", true),
+            ("[!a]*.txt", "a.txt", false),
+            ("[abc].txt", "c.txt", true),
+            ("[abc", "[abc", true),
+            ("[abc", "a", false),
+            ("data[", "data[", true),
+            ("dir*", "dir1/", true),
+        ];
+
+        for (glob, path, matches) in cases {
+            let regex = QueryTerm::glob_regex(glob)
+                .unwrap_or_else(|error| panic!("{error}"));
+
+            assert_eq!(regex.is_match(path), matches, "{glob} {path}");
+        }
+    }
+
+    #[test]
+    fn highlights_deleted_entries() {
+        let deleted = item(RsyncMsgType::Info, "*deleting", "deleting dir/Old.log");
+
+        assert_eq!(highlights("*.log", &deleted), [(13, 20)]);
+        assert_eq!(highlights("dir/*.log", &deleted), [(9, 20)]);
+        assert_eq!(highlights("path:dir/", &deleted), [(9, 13)]);
+        assert_eq!(highlights("old", &deleted), [(13, 16)]);
+        assert_eq!(highlights("change:deleted", &deleted), []);
+    }
+
+    #[test]
+    fn highlights_symlink_entries() {
+        let link = item(RsyncMsgType::L, "cL+++++++++", "bin/run.sh -> ../tools/run.sh");
+
+        // Globs and paths match the link name, not the target
+        assert_eq!(highlights("*.sh", &link), [(4, 10)]);
+        assert_eq!(highlights("path:bin/", &link), [(0, 4)]);
+        assert!(OutputQuery::parse("tools/*").is_ok_and(|query| !query.matches(&link)));
+
+        // Text and regexes match the whole message
+        assert_eq!(highlights("run", &link), [(4, 7), (23, 26)]);
+        assert_eq!(highlights("/-> \\.\\./", &link), [(11, 16)]);
+    }
+}
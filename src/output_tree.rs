@@ -0,0 +1,325 @@
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+
+use crate::{
+    output_window::OutputObject,
+    rsync_process::RsyncMsgType,
+    utils::convert
+};
+
+//------------------------------------------------------------------------------
+// ENUM: ChangeKind
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ChangeKind {
+    #[default]
+    Unchanged,
+    New,
+    Updated,
+    Deleted
+}
+
+impl ChangeKind {
+    //---------------------------------------
+    // From changes function
+    //---------------------------------------
+    // Classifies an itemize changes string (YXcstpoguax)
+    pub fn from_changes(changes: &str) -> Self {
+        if changes.starts_with("*deleting") {
+            return Self::Deleted;
+        }
+
+        let Some(attrs) = changes.get(2..).filter(|attrs| !attrs.is_empty()) else {
+            return Self::Unchanged;
+        };
+
+        if attrs.chars().all(|c| c == '+') {
+            Self::New
+        } else if changes.starts_with(['<', '>']) || attrs.chars().any(|c| c != '.' && c != ' ') {
+            Self::Updated
+        } else {
+            Self::Unchanged
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: ChangeCounts
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ChangeCounts {
+    pub new: u32,
+    pub updated: u32,
+    pub deleted: u32
+}
+
+impl ChangeCounts {
+    //---------------------------------------
+    // Add function
+    //---------------------------------------
+    pub fn add(&mut self, kind: ChangeKind) {
+        match kind {
+            ChangeKind::New => self.new += 1,
+            ChangeKind::Updated => self.updated += 1,
+            ChangeKind::Deleted => self.deleted += 1,
+            ChangeKind::Unchanged => {}
+        }
+    }
+
+    //---------------------------------------
+    // Merge function
+    //---------------------------------------
+    pub fn merge(&mut self, other: &Self) {
+        self.new += other.new;
+        self.updated += other.updated;
+        self.deleted += other.deleted;
+    }
+
+    //---------------------------------------
+    // Label function
+    //---------------------------------------
+    pub fn label(&self) -> String {
+        [(self.new, "new"), (self.updated, "updated"), (self.deleted, "deleted")].into_iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, name)| format!("{count} {name}"))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: OutputTreeNode
+//------------------------------------------------------------------------------
+#[derive(Default, Debug)]
+pub struct OutputTreeNode {
+    pub name: String,
+    pub item: Option<OutputObject>,
+    pub is_dir: bool,
+    pub counts: ChangeCounts,
+    pub size: u64,
+    pub transferred: u64,
+    pub children: Vec<Rc<OutputTreeNode>>
+}
+
+impl OutputTreeNode {
+    //---------------------------------------
+    // Build function
+    //---------------------------------------
+    // Nests itemized entries by directory, returns top level nodes
+    pub fn build(items: impl Iterator<Item = OutputObject>) -> Vec<Rc<Self>> {
+        let mut root = NodeBuilder::default();
+
+        for item in items {
+            let Some(path) = item.path().map(ToOwned::to_owned) else {
+                continue;
+            };
+
+            let is_dir = path.ends_with('/');
+
+            let components: Vec<String> = path.split('/')
+                .filter(|c| !c.is_empty() && *c != ".")
+                .map(ToOwned::to_owned)
+                .collect();
+
+            if components.is_empty() {
+                continue;
+            }
+
+            let node = components.into_iter()
+                .fold(&mut root, |node, name| node.children.entry(name).or_default());
+
+            node.is_dir |= is_dir;
+            node.item = Some(item);
+        }
+
+        root.children.into_iter()
+            .map(|(name, builder)| Rc::new(builder.into_node(name)))
+            .collect()
+    }
+
+    //---------------------------------------
+    // Change kind function
+    //---------------------------------------
+    pub fn change_kind(&self) -> ChangeKind {
+        self.item.as_ref()
+            .map_or(ChangeKind::Unchanged, |item| ChangeKind::from_changes(&item.changes))
+    }
+
+    //---------------------------------------
+    // Summary function
+    //---------------------------------------
+    // Aggregated counts and byte totals of directory
+    pub fn summary(&self) -> String {
+        let mut summary = self.counts.label();
+
+        if self.size > 0 {
+            if !summary.is_empty() {
+                summary.push_str(" · ");
+            }
+
+            summary.push_str(&format!("{}B", convert::num_to_size(self.size as f64)));
+
+            if self.transferred > 0 {
+                summary.push_str(&format!(" ({}B transferred)", convert::num_to_size(self.transferred as f64)));
+            }
+        }
+
+        summary
+    }
+
+    //---------------------------------------
+    // Icon name function
+    //---------------------------------------
+    pub fn icon_name(&self) -> &'static str {
+        if self.change_kind() == ChangeKind::Deleted {
+            return "stats-deleted-symbolic";
+        }
+
+        if self.is_dir {
+            return "stats-dir-symbolic";
+        }
+
+        match self.item.as_ref().map(|item| item.tag) {
+            Some(RsyncMsgType::L) => "stats-link-symbolic",
+            Some(RsyncMsgType::D | RsyncMsgType::S) => "stats-special-symbolic",
+            _ => "stats-file-symbolic"
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: NodeBuilder
+//------------------------------------------------------------------------------
+#[derive(Default)]
+struct NodeBuilder {
+    item: Option<OutputObject>,
+    is_dir: bool,
+    children: IndexMap<String, NodeBuilder>
+}
+
+impl NodeBuilder {
+    //---------------------------------------
+    // Into node function
+    //---------------------------------------
+    fn into_node(self, name: String) -> OutputTreeNode {
+        let children: Vec<Rc<OutputTreeNode>> = self.children.into_iter()
+            .map(|(name, builder)| Rc::new(builder.into_node(name)))
+            .collect();
+
+        let mut node = OutputTreeNode {
+            name,
+            is_dir: self.is_dir || !children.is_empty() || self.item.as_ref().is_some_and(|item| item.tag == RsyncMsgType::d),
+            item: self.item,
+            ..Default::default()
+        };
+
+        // Aggregate counts and bytes of node and descendants (ignore dir and deleted entries)
+        let kind = node.change_kind();
+
+        node.counts.add(kind);
+
+        if kind != ChangeKind::Deleted && let Some(item) = node.item.as_ref().filter(|item| item.tag != RsyncMsgType::d) {
+            node.size = item.size;
+            node.transferred = item.transferred;
+        }
+
+        for child in &children {
+            node.counts.merge(&child.counts);
+
+            node.size += child.size;
+            node.transferred += child.transferred;
+        }
+
+        node.children = children;
+
+        node
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(tag: RsyncMsgType, changes: &str, msg: &str, size: u64, transferred: u64) -> OutputObject {
+        OutputObject {
+            changes: changes.to_owned(),
+            size,
+            transferred,
+            ..OutputObject::new(tag, msg)
+        }
+    }
+
+    #[test]
+    fn change_kind_from_changes() {
+        let cases = [
+            ("*deleting", ChangeKind::Deleted),
+            (">f+++++++++", ChangeKind::New),
+            ("cd+++++++++", ChangeKind::New),
+            ("cL+++++++++", ChangeKind::New),
+            (">f.st......", ChangeKind::Updated),
+            ("<f..t......", ChangeKind::Updated),
+            (".d..t......", ChangeKind::Updated),
+            (">f.........", ChangeKind::Updated),
+            (".f         ", ChangeKind::Unchanged),
+            (".d.........", ChangeKind::Unchanged),
+            ("", ChangeKind::Unchanged),
+        ];
+
+        for (changes, kind) in cases {
+            assert_eq!(ChangeKind::from_changes(changes), kind, "{changes:?}");
+        }
+    }
+
+    #[test]
+    fn build_nests_and_aggregates() {
+        let nodes = OutputTreeNode::build([
+            item(RsyncMsgType::d, "cd+++++++++", "dir/", 4096, 0),
+            item(RsyncMsgType::f, ">f+++++++++", "dir/a.txt", 100, 100),
+            item(RsyncMsgType::f, ">f.st......", "dir/sub/b.txt", 50, 20),
+            item(RsyncMsgType::Info, "*deleting", "deleting dir/old.txt", 0, 0),
+            item(RsyncMsgType::L, "cL+++++++++", "link -> dir/a.txt", 9, 0),
+            item(RsyncMsgType::f, ".f         ", "./top.txt", 10, 0),
+            item(RsyncMsgType::Info, "", "sending incremental file list", 0, 0),
+        ].into_iter());
+
+        let names: Vec<&str> = nodes.iter().map(|node| node.name.as_str()).collect();
+
+        assert_eq!(names, ["dir", "link", "top.txt"]);
+
+        // Directory entry size is ignored, descendants are aggregated
+        let dir = &nodes[0];
+
+        assert!(dir.is_dir);
+        assert_eq!((dir.counts.new, dir.counts.updated, dir.counts.deleted), (2, 1, 1));
+        assert_eq!((dir.size, dir.transferred), (150, 120));
+        assert_eq!(dir.counts.label(), "2 new, 1 updated, 1 deleted");
+
+        let children: Vec<&str> = dir.children.iter().map(|node| node.name.as_str()).collect();
+
+        assert_eq!(children, ["a.txt", "sub", "old.txt"]);
+
+        // Intermediate directory without its own entry
+        let sub = &dir.children[1];
+
+        assert!(sub.is_dir && sub.item.is_none());
+        assert_eq!((sub.counts.updated, sub.size, sub.transferred), (1, 50, 20));
+
+        // Deleted entries count but add no bytes
+        let old = &dir.children[2];
+
+        assert_eq!(old.change_kind(), ChangeKind::Deleted);
+        assert_eq!(old.icon_name(), "stats-deleted-symbolic");
+        assert_eq!(old.size, 0);
+
+        // Link targets are not part of the path
+        assert_eq!(nodes[1].icon_name(), "stats-link-symbolic");
+        assert_eq!(nodes[1].size, 9);
+
+        assert_eq!(nodes[2].change_kind(), ChangeKind::Unchanged);
+        assert_eq!(nodes[2].counts.label(), "");
+    }
+}
@@ -0,0 +1,103 @@
+use std::rc::Rc;
+
+use gtk::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::glib;
+use glib::BoxedAnyObject;
+
+use crate::output_tree::OutputTreeNode;
+
+//------------------------------------------------------------------------------
+// MODULE: OutputTreeItem
+//------------------------------------------------------------------------------
+mod imp {
+    use super::*;
+
+    //---------------------------------------
+    // Private structure
+    //---------------------------------------
+    #[derive(Default, gtk::CompositeTemplate)]
+    #[template(resource = "/com/github/Syncer/ui/output_tree_item.ui")]
+    pub struct OutputTreeItem {
+        #[template_child]
+        pub(super) expander: TemplateChild<gtk::TreeExpander>,
+        #[template_child]
+        pub(super) image: TemplateChild<gtk::Image>,
+        #[template_child]
+        pub(super) label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) counts_label: TemplateChild<gtk::Label>,
+    }
+
+    //---------------------------------------
+    // Subclass
+    //---------------------------------------
+    #[glib::object_subclass]
+    impl ObjectSubclass for OutputTreeItem {
+        const NAME: &'static str = "OutputTreeItem";
+        type Type = super::OutputTreeItem;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for OutputTreeItem {}
+    impl WidgetImpl for OutputTreeItem {}
+    impl BoxImpl for OutputTreeItem {}
+}
+
+//------------------------------------------------------------------------------
+// IMPLEMENTATION: OutputTreeItem
+//------------------------------------------------------------------------------
+glib::wrapper! {
+    pub struct OutputTreeItem(ObjectSubclass<imp::OutputTreeItem>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl OutputTreeItem {
+    //---------------------------------------
+    // Bind function
+    //---------------------------------------
+    pub fn bind(&self, row: &gtk::TreeListRow) {
+        let imp = self.imp();
+
+        imp.expander.set_list_row(Some(row));
+
+        let obj = row.item()
+            .and_downcast::<BoxedAnyObject>()
+            .expect("Could not downcast to 'BoxedAnyObject'");
+
+        let node = obj.borrow::<Rc<OutputTreeNode>>();
+
+        imp.image.set_icon_name(Some(node.icon_name()));
+        imp.label.set_label(&node.name);
+
+        let counts = if node.is_dir { node.summary() } else { String::new() };
+
+        imp.counts_label.set_label(&counts);
+        imp.counts_label.set_visible(!counts.is_empty());
+    }
+
+    //---------------------------------------
+    // Unbind function
+    //---------------------------------------
+    pub fn unbind(&self) {
+        self.imp().expander.set_list_row(None);
+    }
+}
+
+impl Default for OutputTreeItem {
+    //---------------------------------------
+    // Default constructor
+    //---------------------------------------
+    fn default() -> Self {
+        glib::Object::builder().build()
+    }
+}
@@ -1,4 +1,8 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell, OnceCell};
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
 use std::time::Duration;
 
 use adw::subclass::prelude::*;
@@ -9,25 +13,100 @@ use glib::{clone, BoxedAnyObject};
 use crate::{
     output_item::OutputItem,
     output_header::OutputHeader,
-    rsync_process::{RsyncMsgType, RsyncMessages}
+    output_tree::OutputTreeNode,
+    output_query::OutputQuery,
+    output_tree_item::OutputTreeItem,
+    rsync_process::{RsyncMsgType, RsyncMessage, RsyncMessages},
+    rsync_error::{RsyncError, RsyncErrorCategory},
+    output_export::{ExportFormat, OutputReport},
+    utils::case
 };
 
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+const FLUSH_INTERVAL: u64 = 200;
+const AUTO_SCROLL_THRESHOLD: f64 = 24.0;
+
 //------------------------------------------------------------------------------
 // STRUCT: OutputObject
 //------------------------------------------------------------------------------
 #[derive(Default, Debug, Clone)]
 pub struct OutputObject {
     pub tag: RsyncMsgType,
-    pub msg: String
+    pub changes: String,
+    pub msg: String,
+    pub size: u64,
+    pub transferred: u64,
+    pub mtime: String,
+    pub owner: String,
+    pub raw_path: Vec<u8>,
+    pub category: Option<RsyncErrorCategory>,
+    pub attempt: Option<usize>
 }
 
 impl OutputObject {
     pub fn new(tag: RsyncMsgType, msg: &str) -> Self {
         Self {
             tag,
-            msg: msg.to_owned()
+            msg: msg.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_message(message: &RsyncMessage, attempt: Option<usize>) -> Self {
+        Self {
+            tag: message.tag,
+            changes: message.changes.clone(),
+            msg: message.msg.clone(),
+            size: message.size,
+            transferred: message.transferred,
+            mtime: message.mtime.clone(),
+            owner: message.owner.clone(),
+            raw_path: message.raw_path.clone(),
+            attempt,
+            ..Default::default()
         }
     }
+
+    pub fn new_error(category: RsyncErrorCategory, msg: &str) -> Self {
+        Self {
+            tag: RsyncMsgType::Error,
+            msg: msg.to_owned(),
+            category: Some(category),
+            ..Default::default()
+        }
+    }
+
+    pub fn new_attempt(attempt: usize, tag: RsyncMsgType, msg: &str) -> Self {
+        Self {
+            tag,
+            msg: msg.to_owned(),
+            attempt: Some(attempt),
+            ..Default::default()
+        }
+    }
+
+    //---------------------------------------
+    // Path function
+    //---------------------------------------
+    // Returns the path of itemized and deleted entries (without link targets)
+    pub fn path(&self) -> Option<&str> {
+        let path = match self.tag {
+            RsyncMsgType::f | RsyncMsgType::d | RsyncMsgType::L | RsyncMsgType::D | RsyncMsgType::S => {
+                self.msg.as_str()
+            }
+            RsyncMsgType::Info if self.changes.starts_with("*deleting") => {
+                self.msg.split_once(' ')?.1
+            }
+            _ => return None
+        };
+
+        let path = path.split_once(" -> ").map_or(path, |(path, _)| path);
+        let path = path.split_once(" => ").map_or(path, |(path, _)| path);
+
+        Some(path)
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -74,7 +153,7 @@ mod imp {
         #[template_child]
         pub(super) selection: TemplateChild<gtk::NoSelection>,
         #[template_child]
-        pub(super) error_model: TemplateChild<gio::ListStore>,
+        pub(super) section_model: TemplateChild<gio::ListStore>,
         #[template_child]
         pub(super) stat_model: TemplateChild<gio::ListStore>,
         #[template_child]
@@ -87,9 +166,41 @@ mod imp {
         pub(super) item_factory: TemplateChild<gtk::SignalListItemFactory>,
         #[template_child]
         pub(super) header_factory: TemplateChild<gtk::SignalListItemFactory>,
+        #[template_child]
+        pub(super) view_stack: TemplateChild<gtk::Stack>,
+        #[template_child]
+        pub(super) tree_view: TemplateChild<gtk::ListView>,
+        #[template_child]
+        pub(super) tree_selection: TemplateChild<gtk::NoSelection>,
+        #[template_child]
+        pub(super) tree_factory: TemplateChild<gtk::SignalListItemFactory>,
 
         #[property(get, set, builder(FilterType::default()))]
         filter_type: Cell<FilterType>,
+        #[property(get, set)]
+        tree_mode: Cell<bool>,
+        #[property(get, set)]
+        sort_by_size: Cell<bool>,
+
+        pub(super) extra_sections: Cell<u32>,
+
+        pub(super) query: RefCell<OutputQuery>,
+        pub(super) message_order: RefCell<Vec<BoxedAnyObject>>,
+
+        pub(super) streaming: Cell<bool>,
+        pub(super) auto_scroll: Cell<bool>,
+        pub(super) pending: RefCell<Vec<OutputObject>>,
+        pub(super) flush_source: RefCell<Option<glib::SourceId>>,
+        pub(super) live_error_model: RefCell<Option<gio::ListStore>>,
+
+        pub(super) summary_title: RefCell<String>,
+        pub(super) summary_status: RefCell<String>,
+
+        pub(super) source_root: RefCell<String>,
+        pub(super) destination_root: RefCell<String>,
+
+        pub(super) tree_model: OnceCell<gtk::TreeListModel>,
+        pub(super) tree_rebuild_source: RefCell<Option<glib::SourceId>>,
     }
 
     //---------------------------------------
@@ -139,6 +250,24 @@ mod imp {
         fn install_actions(klass: &mut <Self as ObjectSubclass>::Class) {
             // Filter type property action
             klass.install_property_action("filter.type", "filter-type");
+
+            // Tree mode property action
+            klass.install_property_action("output.tree-mode", "tree-mode");
+
+            // Sort by size property action
+            klass.install_property_action("output.sort-size", "sort-by-size");
+
+            // Export log action
+            klass.install_action_async("output.export", Some(glib::VariantTy::BOOLEAN),
+                async |window, _, param| {
+                    // Get filtered
+                    let filtered = param
+                        .and_then(|param| param.get::<bool>())
+                        .expect("Could not get bool from variant");
+
+                    window.export_log(filtered).await;
+                }
+            );
         }
 
         //---------------------------------------
@@ -217,6 +346,49 @@ impl OutputWindow {
             imp.filter_button.set_icon_name(icon);
         });
 
+        // Sort by size property notify signal
+        self.connect_sort_by_size_notify(|window| {
+            let imp = window.imp();
+
+            window.show_spinner(true);
+
+            if window.sort_by_size() {
+                // Save original order and sort messages by size (largest first)
+                let order: Vec<BoxedAnyObject> = imp.message_model.iter::<BoxedAnyObject>()
+                    .flatten()
+                    .collect();
+
+                let mut sorted = order.clone();
+                sorted.sort_by_cached_key(|obj| std::cmp::Reverse(obj.borrow::<OutputObject>().size));
+
+                imp.message_order.replace(order);
+
+                imp.message_model.splice(0, imp.message_model.n_items(), &sorted);
+            } else {
+                // Restore original order
+                let order = imp.message_order.take();
+
+                imp.message_model.splice(0, imp.message_model.n_items(), &order);
+            }
+        });
+
+        // Tree mode property notify signal
+        self.connect_tree_mode_notify(|window| {
+            let imp = window.imp();
+
+            if window.tree_mode() {
+                imp.view_stack.set_visible_child_name("tree");
+                imp.search_entry.set_key_capture_widget(Some(&imp.tree_view.get()));
+
+                window.rebuild_tree();
+            } else {
+                imp.view_stack.set_visible_child_name("list");
+                imp.search_entry.set_key_capture_widget(Some(&imp.view.get()));
+
+                window.clear_tree();
+            }
+        });
+
         // Item factory setup signal
         imp.item_factory.connect_setup(|_, obj| {
             let item = obj
@@ -227,21 +399,26 @@ impl OutputWindow {
         });
 
         // Item factory bind signal
-        imp.item_factory.connect_bind(|_, obj| {
-            let item = obj
-                .downcast_ref::<gtk::ListItem>()
-                .expect("Could not downcast to 'GtkListItem'");
+        imp.item_factory.connect_bind(clone!(
+            #[weak] imp,
+            move |_, obj| {
+                let item = obj
+                    .downcast_ref::<gtk::ListItem>()
+                    .expect("Could not downcast to 'GtkListItem'");
 
-            let child = item.child()
-                .and_downcast::<OutputItem>()
-                .expect("Could not downcast to 'OutputItem'");
+                let child = item.child()
+                    .and_downcast::<OutputItem>()
+                    .expect("Could not downcast to 'OutputItem'");
 
-            let output_object = item.item()
-                .and_downcast::<BoxedAnyObject>()
-                .expect("Could not downcast to 'BoxedAnyObject'");
+                let output_object = item.item()
+                    .and_downcast::<BoxedAnyObject>()
+                    .expect("Could not downcast to 'BoxedAnyObject'");
 
-            child.bind(&output_object.borrow());
-        });
+                let output_object = output_object.borrow::<OutputObject>();
+
+                child.bind(&output_object, &imp.query.borrow().highlights(&output_object));
+            }
+        ));
 
         // Header factory setup signal
         imp.header_factory.connect_setup(|_, obj| {
@@ -269,6 +446,59 @@ impl OutputWindow {
             child.bind(&output_object.borrow());
         });
 
+        // Tree factory setup signal
+        imp.tree_factory.connect_setup(|_, obj| {
+            let item = obj
+                .downcast_ref::<gtk::ListItem>()
+                .expect("Could not downcast to 'GtkListItem'");
+
+            item.set_child(Some(&OutputTreeItem::default()));
+        });
+
+        // Tree factory bind signal
+        imp.tree_factory.connect_bind(|_, obj| {
+            let item = obj
+                .downcast_ref::<gtk::ListItem>()
+                .expect("Could not downcast to 'GtkListItem'");
+
+            let child = item.child()
+                .and_downcast::<OutputTreeItem>()
+                .expect("Could not downcast to 'OutputTreeItem'");
+
+            let row = item.item()
+                .and_downcast::<gtk::TreeListRow>()
+                .expect("Could not downcast to 'GtkTreeListRow'");
+
+            child.bind(&row);
+        });
+
+        // Tree factory unbind signal
+        imp.tree_factory.connect_unbind(|_, obj| {
+            let item = obj
+                .downcast_ref::<gtk::ListItem>()
+                .expect("Could not downcast to 'GtkListItem'");
+
+            if let Some(child) = item.child().and_downcast::<OutputTreeItem>() {
+                child.unbind();
+            }
+        });
+
+        // Filter changed signal (refilter tree)
+        imp.filter.connect_changed(clone!(
+            #[weak(rename_to = window)] self,
+            move |_, _| {
+                window.queue_tree_rebuild();
+            }
+        ));
+
+        // Message model items changed signal (add new items to tree)
+        imp.message_model.connect_items_changed(clone!(
+            #[weak(rename_to = window)] self,
+            move |_, _, _, _| {
+                window.queue_tree_rebuild();
+            }
+        ));
+
         // Search entry search started signal
         imp.search_entry.connect_search_started(|entry| {
             if !entry.has_focus() {
@@ -279,20 +509,48 @@ impl OutputWindow {
         // Search entry search changed signal
         imp.search_entry.connect_search_changed(clone!(
             #[weak(rename_to = window)] self,
-            move |_| {
+            move |entry| {
+                let imp = window.imp();
+
+                // Parse query (match everything if invalid)
+                match OutputQuery::parse(&entry.text()) {
+                    Ok(query) => {
+                        entry.remove_css_class("error");
+                        entry.set_tooltip_text(None);
+
+                        imp.query.replace(query);
+                    }
+                    Err(error) => {
+                        entry.add_css_class("error");
+                        entry.set_tooltip_text(Some(&error));
+
+                        imp.query.replace(OutputQuery::default());
+                    }
+                }
+
                 window.show_spinner(true);
 
-                window.imp().filter.changed(gtk::FilterChange::Different);
+                imp.filter.changed(gtk::FilterChange::Different);
+
+                window.update_count_label();
             }
         ));
 
         // Selection items changed signal
         imp.selection.connect_items_changed(clone!(
-            #[weak] imp,
-            move |selection, _, _, _| {
-                let n_items = selection.n_items();
+            #[weak(rename_to = window)] self,
+            move |_, _, _, _| {
+                window.update_count_label();
+            }
+        ));
 
-                imp.header_sub_label.set_label(&format!("{n_items} item{}", if n_items == 1 { "" } else { "s" }));
+        // Scroll window vertical adjustment value changed signal (pause auto-scroll if scrolled up)
+        imp.scroll_window.vadjustment().connect_value_changed(clone!(
+            #[weak] imp,
+            move |adjust| {
+                if imp.streaming.get() {
+                    imp.auto_scroll.set(adjust.value() + adjust.page_size() >= adjust.upper() - AUTO_SCROLL_THRESHOLD);
+                }
             }
         ));
 
@@ -316,6 +574,33 @@ impl OutputWindow {
         // Set search entry key capture widget
         imp.search_entry.set_key_capture_widget(Some(&imp.view.get()));
 
+        // Create tree model (children created on demand when expanded)
+        let tree_model = gtk::TreeListModel::new(gio::ListStore::new::<BoxedAnyObject>(), false, false, |obj| {
+            let node = obj
+                .downcast_ref::<BoxedAnyObject>()
+                .expect("Could not downcast to 'BoxedAnyObject'")
+                .borrow::<Rc<OutputTreeNode>>();
+
+            if node.children.is_empty() {
+                return None;
+            }
+
+            let children: Vec<BoxedAnyObject> = node.children.iter()
+                .cloned()
+                .map(BoxedAnyObject::new)
+                .collect();
+
+            let model = gio::ListStore::new::<BoxedAnyObject>();
+            model.splice(0, 0, &children);
+
+            Some(model.upcast())
+        });
+
+        imp.tree_selection.set_model(Some(&tree_model));
+
+        imp.tree_model.set(tree_model)
+            .expect("Could not set tree model");
+
         // Set filter function
         imp.filter.set_filter_func(clone!(
             #[weak(rename_to = window)] self,
@@ -328,12 +613,9 @@ impl OutputWindow {
                     .borrow::<OutputObject>();
 
                 let tag = output_object.tag;
-                let msg = &output_object.msg;
-
-                let search = imp.search_entry.text();
 
-                // Return if message text doesn’t contain the search string (ignore case)
-                if !msg.to_ascii_lowercase().contains(&search.to_ascii_lowercase()) {
+                // Return if message doesn’t match the search query
+                if !imp.query.borrow().matches(&output_object) {
                     return false;
                 }
 
@@ -369,18 +651,390 @@ impl OutputWindow {
         imp.search_entry.add_controller(controller);
     }
 
+    //---------------------------------------
+    // Append messages helper function
+    //---------------------------------------
+    fn append_messages(&self, messages: &[BoxedAnyObject]) {
+        let imp = self.imp();
+
+        if self.sort_by_size() {
+            imp.message_order.borrow_mut().extend_from_slice(messages);
+
+            let size = |obj: &BoxedAnyObject| obj.borrow::<OutputObject>().size;
+
+            // Sort chunk by size (descending, stable)
+            let mut chunk = messages.to_vec();
+            chunk.sort_by_key(|obj| std::cmp::Reverse(size(obj)));
+
+            let Some(largest) = chunk.first().map(size) else {
+                return;
+            };
+
+            let model = &imp.message_model;
+            let n_items = model.n_items();
+
+            // Find first position affected by the chunk (items before it are kept)
+            let (mut start, mut end) = (0, n_items);
+
+            while start < end {
+                let mid = start + (end - start) / 2;
+
+                if model.item(mid).and_downcast::<BoxedAnyObject>().is_some_and(|obj| size(&obj) >= largest) {
+                    start = mid + 1;
+                } else {
+                    end = mid;
+                }
+            }
+
+            // Merge chunk with remaining items and replace them at once
+            let mut existing = (start..n_items)
+                .filter_map(|i| model.item(i).and_downcast::<BoxedAnyObject>())
+                .peekable();
+
+            let mut chunk = chunk.into_iter().peekable();
+
+            let mut merged = Vec::with_capacity((n_items - start) as usize + messages.len());
+
+            loop {
+                let next = match (existing.peek(), chunk.peek()) {
+                    (Some(a), Some(b)) if size(a) >= size(b) => existing.next(),
+                    (_, Some(_)) => chunk.next(),
+                    (Some(_), None) => existing.next(),
+                    (None, None) => break
+                };
+
+                merged.extend(next);
+            }
+
+            model.splice(start, n_items - start, &merged);
+        } else {
+            imp.message_model.splice(imp.message_model.n_items(), 0, messages);
+        }
+    }
+
+    //---------------------------------------
+    // Update count label helper function
+    //---------------------------------------
+    fn update_count_label(&self) {
+        let imp = self.imp();
+
+        let n_items = imp.selection.n_items();
+
+        let label = if imp.query.borrow().is_empty() {
+            format!("{n_items} item{}", if n_items == 1 { "" } else { "s" })
+        } else {
+            let total = imp.filter_model.model()
+                .map_or(0, |model| model.n_items());
+
+            format!("{n_items} match{} of {total} items", if n_items == 1 { "" } else { "es" })
+        };
+
+        imp.header_sub_label.set_label(&label);
+    }
+
+    //---------------------------------------
+    // Queue tree rebuild helper function
+    //---------------------------------------
+    fn queue_tree_rebuild(&self) {
+        let imp = self.imp();
+
+        // Skip rebuilds while streaming (tree is rebuilt when streaming stops)
+        if !self.tree_mode() || imp.streaming.get() || imp.tree_rebuild_source.borrow().is_some() {
+            return;
+        }
+
+        // Batch rebuilds while messages are loading
+        let source = glib::timeout_add_local_once(Duration::from_millis(FLUSH_INTERVAL), clone!(
+            #[weak(rename_to = window)] self,
+            move || {
+                window.imp().tree_rebuild_source.replace(None);
+
+                window.rebuild_tree();
+            }
+        ));
+
+        imp.tree_rebuild_source.replace(Some(source));
+    }
+
+    //---------------------------------------
+    // Rebuild tree helper function
+    //---------------------------------------
+    fn rebuild_tree(&self) {
+        let imp = self.imp();
+
+        let Some(tree_model) = imp.tree_model.get() else {
+            return;
+        };
+
+        // Build tree from messages matching search and filter
+        let items = imp.message_model.iter::<BoxedAnyObject>()
+            .flatten()
+            .filter(|obj| imp.filter.match_(obj))
+            .map(|obj| obj.borrow::<OutputObject>().clone());
+
+        let nodes: Vec<BoxedAnyObject> = OutputTreeNode::build(items).into_iter()
+            .map(BoxedAnyObject::new)
+            .collect();
+
+        // Expand matches while searching
+        tree_model.set_autoexpand(!imp.search_entry.text().is_empty());
+
+        let root = tree_model.model()
+            .downcast::<gio::ListStore>()
+            .expect("Could not downcast to 'GListStore'");
+
+        // Restore expanded nodes after replacing tree
+        let expanded = Self::expanded_tree_paths(tree_model);
+
+        root.splice(0, root.n_items(), &nodes);
+
+        if expanded.is_empty() {
+            return;
+        }
+
+        let mut path: Vec<String> = vec![];
+        let mut pos = 0;
+
+        // Rows of expanded nodes are inserted after their parent
+        while let Some(row) = tree_model.row(pos) {
+            Self::push_row_path(&mut path, &row);
+
+            if expanded.contains(&path.join("/")) {
+                row.set_expanded(true);
+            }
+
+            pos += 1;
+        }
+    }
+
+    //---------------------------------------
+    // Expanded tree paths helper function
+    //---------------------------------------
+    fn expanded_tree_paths(tree_model: &gtk::TreeListModel) -> HashSet<String> {
+        let mut expanded = HashSet::new();
+
+        let mut path: Vec<String> = vec![];
+        let mut pos = 0;
+
+        while let Some(row) = tree_model.row(pos) {
+            Self::push_row_path(&mut path, &row);
+
+            if row.is_expanded() {
+                expanded.insert(path.join("/"));
+            }
+
+            pos += 1;
+        }
+
+        expanded
+    }
+
+    //---------------------------------------
+    // Push row path helper function
+    //---------------------------------------
+    fn push_row_path(path: &mut Vec<String>, row: &gtk::TreeListRow) {
+        path.truncate(row.depth() as usize);
+
+        if let Some(obj) = row.item().and_downcast::<BoxedAnyObject>() {
+            path.push(obj.borrow::<Rc<OutputTreeNode>>().name.clone());
+        }
+    }
+
+    //---------------------------------------
+    // Clear tree helper function
+    //---------------------------------------
+    fn clear_tree(&self) {
+        let imp = self.imp();
+
+        if let Some(source) = imp.tree_rebuild_source.take() {
+            source.remove();
+        }
+
+        if let Some(root) = imp.tree_model.get().and_then(|model| model.model().downcast::<gio::ListStore>().ok()) {
+            root.remove_all();
+        }
+    }
+
+    //---------------------------------------
+    // Start streaming function
+    //---------------------------------------
+    pub fn start_streaming(&self) {
+        let imp = self.imp();
+
+        self.clear_messages();
+
+        imp.streaming.set(true);
+        imp.auto_scroll.set(true);
+
+        // Allow stopping the transfer while the window is open
+        self.set_modal(false);
+
+        // Add pending messages to model in batches
+        let source = glib::timeout_add_local(Duration::from_millis(FLUSH_INTERVAL), clone!(
+            #[weak(rename_to = window)] self,
+            #[upgrade_or] glib::ControlFlow::Break,
+            move || {
+                window.flush_pending();
+
+                glib::ControlFlow::Continue
+            }
+        ));
+
+        imp.flush_source.replace(Some(source));
+    }
+
+    //---------------------------------------
+    // Append output function
+    //---------------------------------------
+    pub fn append_output(&self, message: &RsyncMessage) {
+        let imp = self.imp();
+
+        if imp.streaming.get() {
+            imp.pending.borrow_mut().push(OutputObject::from_message(message, None));
+        }
+    }
+
+    //---------------------------------------
+    // Flush pending helper function
+    //---------------------------------------
+    fn flush_pending(&self) {
+        let imp = self.imp();
+
+        let pending = imp.pending.take();
+
+        if pending.is_empty() {
+            return;
+        }
+
+        let (errors, messages): (Vec<OutputObject>, Vec<OutputObject>) = pending.into_iter()
+            .partition(|obj| obj.tag == RsyncMsgType::Error);
+
+        // Add errors to live error section
+        if !errors.is_empty() {
+            let error_model = imp.live_error_model.borrow_mut()
+                .get_or_insert_with(|| {
+                    let error_model = gio::ListStore::new::<BoxedAnyObject>();
+
+                    imp.section_model.insert(0, &error_model);
+                    imp.extra_sections.set(imp.extra_sections.get() + 1);
+
+                    error_model
+                })
+                .clone();
+
+            let errors: Vec<BoxedAnyObject> = errors.into_iter()
+                .map(BoxedAnyObject::new)
+                .collect();
+
+            error_model.splice(error_model.n_items(), 0, &errors);
+        }
+
+        // Add messages to model
+        let messages: Vec<BoxedAnyObject> = messages.into_iter()
+            .map(BoxedAnyObject::new)
+            .collect();
+
+        self.append_messages(&messages);
+
+        // Scroll to end
+        if imp.auto_scroll.get() && self.is_visible() {
+            let n_items = imp.selection.n_items();
+
+            if n_items > 0 {
+                imp.view.scroll_to(n_items - 1, gtk::ListScrollFlags::NONE, None);
+            }
+        }
+    }
+
+    //---------------------------------------
+    // Stop streaming helper function
+    //---------------------------------------
+    fn stop_streaming(&self) {
+        let imp = self.imp();
+
+        if let Some(source) = imp.flush_source.take() {
+            source.remove();
+        }
+
+        imp.pending.borrow_mut().clear();
+
+        imp.streaming.set(false);
+
+        self.set_modal(true);
+
+        // Rebuild tree skipped while streaming
+        self.queue_tree_rebuild();
+    }
+
     //---------------------------------------
     // Load messages function
     //---------------------------------------
     pub fn load_messages(&self, messages: &RsyncMessages) {
         let imp = self.imp();
 
-        // Add errors to model
-        let errors: Vec<BoxedAnyObject> = messages.errors.iter()
-            .map(|msg| BoxedAnyObject::new(OutputObject::new(RsyncMsgType::Error, msg)))
+        // Stop streaming (messages already in model)
+        let streamed = imp.streaming.get();
+
+        if streamed {
+            self.flush_pending();
+            self.stop_streaming();
+
+            // Replace live error section with error categories
+            if imp.live_error_model.take().is_some() {
+                imp.section_model.remove(0);
+                imp.extra_sections.set(imp.extra_sections.get() - 1);
+            }
+        }
+
+        // Add errors to model (one section per error category)
+        let error_models: Vec<gio::ListStore> = RsyncError::group_errors(&messages.errors).into_iter()
+            .map(|(category, errors)| {
+                // Show affected file paths for vanished files
+                let errors = if category == RsyncErrorCategory::Vanished {
+                    let files = RsyncError::vanished_files(&errors);
+
+                    if files.is_empty() { errors } else { files }
+                } else {
+                    errors
+                };
+
+                let errors: Vec<BoxedAnyObject> = errors.iter()
+                    .map(|msg| BoxedAnyObject::new(OutputObject::new_error(category, msg)))
+                    .collect();
+
+                let error_model = gio::ListStore::new::<BoxedAnyObject>();
+                error_model.splice(0, 0, &errors);
+
+                error_model
+            })
+            .collect();
+
+        // Add log of failed attempts to model (one section per attempt)
+        let attempt_models: Vec<gio::ListStore> = messages.attempts.iter()
+            .enumerate()
+            .map(|(i, (code, attempt))| {
+                let error = RsyncError::classify(*code, &attempt.errors);
+
+                let summary = OutputObject::new_attempt(i + 1, RsyncMsgType::Info,
+                    &format!("Attempt failed: {} (code {code})", error.message));
+
+                let items: Vec<BoxedAnyObject> = [summary].into_iter()
+                    .chain(attempt.errors.iter().map(|msg| OutputObject::new_attempt(i + 1, RsyncMsgType::Error, msg)))
+                    .chain(attempt.messages.iter().map(|message| OutputObject::from_message(message, Some(i + 1))))
+                    .map(BoxedAnyObject::new)
+                    .collect();
+
+                let attempt_model = gio::ListStore::new::<BoxedAnyObject>();
+                attempt_model.splice(0, 0, &items);
+
+                attempt_model
+            })
             .collect();
 
-        imp.error_model.splice(0, 0, &errors);
+        imp.section_model.splice(0, 0, &error_models);
+        imp.section_model.splice(error_models.len() as u32, 0, &attempt_models);
+
+        imp.extra_sections.set((error_models.len() + attempt_models.len()) as u32);
 
         // Add stats to model
         let stats: Vec<BoxedAnyObject> = messages.stats.iter()
@@ -389,6 +1043,10 @@ impl OutputWindow {
 
         imp.stat_model.splice(0, 0, &stats);
 
+        if streamed {
+            return;
+        }
+
         // Spawn task to process messages
         let (sender, receiver) = async_channel::bounded(10);
 
@@ -406,36 +1064,248 @@ impl OutputWindow {
 
         // Attach receiver for task
         glib::spawn_future_local(clone!(
-            #[weak] imp,
+            #[weak(rename_to = window)] self,
             async move {
                 while let Ok(chunk) = receiver.recv().await {
                     // Add messages to model
                     let messages: Vec<BoxedAnyObject> = chunk.iter()
-                        .map(|(flag, msg)| BoxedAnyObject::new(OutputObject::new(*flag, msg)))
+                        .map(|message| BoxedAnyObject::new(OutputObject::from_message(message, None)))
                         .collect();
 
-                    imp.message_model.splice(imp.message_model.n_items(), 0, &messages);
+                    window.append_messages(&messages);
                 }
 
                 // Set initial focus on view
-                imp.view.grab_focus();
+                window.imp().view.grab_focus();
             }
         ));
     }
 
+    //---------------------------------------
+    // Set summary function
+    //---------------------------------------
+    pub fn set_summary(&self, title: &str, status: &str) {
+        let imp = self.imp();
+
+        imp.summary_title.replace(title.to_owned());
+        imp.summary_status.replace(status.to_owned());
+    }
+
+    //---------------------------------------
+    // Set roots function
+    //---------------------------------------
+    pub fn set_roots(&self, source: &str, destination: &str) {
+        let imp = self.imp();
+
+        imp.source_root.replace(source.to_owned());
+        imp.destination_root.replace(destination.to_owned());
+    }
+
+    //---------------------------------------
+    // Source base helper function
+    //---------------------------------------
+    fn source_base(&self) -> String {
+        Self::base_path(&self.imp().source_root.borrow())
+    }
+
+    //---------------------------------------
+    // Base path helper function
+    //---------------------------------------
+    // Without trailing slash, rsync creates the source dir in the destination
+    fn base_path(source: &str) -> String {
+        if source.ends_with('/') {
+            return source.to_owned();
+        }
+
+        match source.rsplit_once('/') {
+            // Root level source (e.g. "/docs" or "host:/docs")
+            Some((parent, _)) if parent.is_empty() || parent.ends_with(':') => format!("{parent}/"),
+            Some((parent, _)) => parent.to_owned(),
+            None => String::new()
+        }
+    }
+
+    //---------------------------------------
+    // Source location function
+    //---------------------------------------
+    pub fn source_location(&self, path: &str) -> String {
+        Self::join_location(&self.source_base(), path)
+    }
+
+    //---------------------------------------
+    // Destination location function
+    //---------------------------------------
+    pub fn destination_location(&self, path: &str) -> String {
+        Self::join_location(&self.imp().destination_root.borrow(), path)
+    }
+
+    //---------------------------------------
+    // Source file function
+    //---------------------------------------
+    pub fn source_file(&self, raw_path: &[u8]) -> Option<gio::File> {
+        Self::join_file(&self.source_base(), raw_path)
+    }
+
+    //---------------------------------------
+    // Destination file function
+    //---------------------------------------
+    pub fn destination_file(&self, raw_path: &[u8]) -> Option<gio::File> {
+        Self::join_file(&self.imp().destination_root.borrow(), raw_path)
+    }
+
+    //---------------------------------------
+    // Join file helper function
+    //---------------------------------------
+    // Joins raw path bytes (may not be valid UTF-8) to local base
+    fn join_file(base: &str, raw_path: &[u8]) -> Option<gio::File> {
+        let raw_path = raw_path.strip_suffix(b"/").unwrap_or(raw_path);
+
+        if raw_path.is_empty() {
+            return None;
+        }
+
+        let base = Self::local_file(if base.is_empty() { "." } else { base })?.path()?;
+
+        Some(gio::File::for_path(base.join(OsStr::from_bytes(raw_path))))
+    }
+
+    //---------------------------------------
+    // Join location helper function
+    //---------------------------------------
+    fn join_location(base: &str, path: &str) -> String {
+        let path = path.trim_end_matches('/');
+
+        if base.is_empty() {
+            path.to_owned()
+        } else {
+            format!("{}/{path}", base.trim_end_matches('/'))
+        }
+    }
+
+    //---------------------------------------
+    // Local file function
+    //---------------------------------------
+    // Returns None for remote locations (host:path or rsync://)
+    pub fn local_file(location: &str) -> Option<gio::File> {
+        if location.is_empty() || location.starts_with("rsync://") {
+            return None;
+        }
+
+        if let Some(colon) = location.find(':') && !location[..colon].contains('/') {
+            return None;
+        }
+
+        Some(gio::File::for_path(location))
+    }
+
+    //---------------------------------------
+    // Export items helper function
+    //---------------------------------------
+    fn export_items(&self, filtered: bool) -> Vec<OutputObject> {
+        let imp = self.imp();
+
+        let to_item = |obj: BoxedAnyObject| obj.borrow::<OutputObject>().clone();
+
+        if filtered {
+            imp.filter_model.iter::<BoxedAnyObject>()
+                .flatten()
+                .map(to_item)
+                .collect()
+        } else {
+            imp.section_model.iter::<gio::ListStore>()
+                .flatten()
+                .flat_map(|model| {
+                    model.iter::<BoxedAnyObject>()
+                        .flatten()
+                        .map(to_item)
+                        .collect::<Vec<OutputObject>>()
+                })
+                .collect()
+        }
+    }
+
+    //---------------------------------------
+    // Export log function
+    //---------------------------------------
+    async fn export_log(&self, filtered: bool) {
+        let imp = self.imp();
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+
+        for (name, suffix) in [("Text Files", "txt"), ("CSV Files", "csv"), ("JSON Files", "json"), ("HTML Reports", "html")] {
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some(name));
+            filter.add_suffix(suffix);
+
+            filters.append(&filter);
+        }
+
+        let dialog = gtk::FileDialog::builder()
+            .title(if filtered { "Export Filtered View" } else { "Export Log" })
+            .modal(true)
+            .initial_name("rsync-log.txt")
+            .filters(&filters)
+            .build();
+
+        let Ok(file) = dialog.save_future(Some(self)).await else {
+            return;
+        };
+
+        let format = file.basename()
+            .map_or(ExportFormat::Text, |name| ExportFormat::from_file_name(&name.to_string_lossy()));
+
+        let report = OutputReport {
+            title: imp.summary_title.borrow().clone(),
+            status: imp.summary_status.borrow().clone(),
+            date: glib::DateTime::now_local()
+                .and_then(|date| date.format("%c"))
+                .map(String::from)
+                .unwrap_or_default(),
+            items: self.export_items(filtered)
+        };
+
+        let contents = format.write(&report);
+
+        if let Err(error) = file.replace_contents_future(contents, None, false, gio::FileCreateFlags::REPLACE_DESTINATION).await {
+            self.error_dialog("Error Exporting Log", &case::capitalize_first(&error.1.to_string()));
+        }
+    }
+
+    //---------------------------------------
+    // Error dialog function
+    //---------------------------------------
+    fn error_dialog(&self, heading: &str, body: &str) {
+        let dialog = adw::AlertDialog::builder()
+            .heading(heading)
+            .body(body)
+            .default_response("close")
+            .build();
+
+        dialog.add_responses(&[("close", "_Close")]);
+
+        dialog.present(Some(self));
+    }
+
     //---------------------------------------
     // Clear messages function
     //---------------------------------------
     pub fn clear_messages(&self) {
         let imp = self.imp();
 
-        imp.error_model.remove_all();
+        self.stop_streaming();
+
+        imp.live_error_model.replace(None);
+
+        imp.section_model.splice(0, imp.extra_sections.replace(0), &[] as &[gio::ListStore]);
         imp.stat_model.remove_all();
         imp.message_model.remove_all();
 
         imp.search_entry.set_text("");
 
         self.set_filter_type(FilterType::default());
+
+        imp.message_order.borrow_mut().clear();
+        self.set_sort_by_size(false);
     }
 
     //---------------------------------------
@@ -448,12 +1318,19 @@ impl OutputWindow {
 
         self.present();
 
-        // Scroll to start
+        // Scroll to start (or end if streaming)
         glib::idle_add_local_once(clone!(
             #[weak] imp,
             move || {
                 let v_adjust = imp.scroll_window.vadjustment();
-                v_adjust.set_value(v_adjust.lower());
+
+                if imp.streaming.get() {
+                    imp.auto_scroll.set(true);
+
+                    v_adjust.set_value(v_adjust.upper() - v_adjust.page_size());
+                } else {
+                    v_adjust.set_value(v_adjust.lower());
+                }
             }
         ));
     }
@@ -467,3 +1344,28 @@ impl Default for OutputWindow {
         glib::Object::builder().build()
     }
 }
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_path() {
+        let paths = [
+            ("/home/user/docs/", "/home/user/docs/"),
+            ("/home/user/docs", "/home/user"),
+            ("/docs", "/"),
+            ("/", "/"),
+            ("host:/docs", "host:/"),
+            ("host:/home/docs", "host:/home"),
+            ("docs", "")
+        ];
+
+        for (source, base) in paths {
+            assert_eq!(OutputWindow::base_path(source), base, "{source}");
+        }
+    }
+}
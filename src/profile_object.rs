@@ -1,14 +1,21 @@
 use std::cell::{Cell, RefCell};
+use std::sync::OnceLock;
 
 use gtk::subclass::prelude::*;
-use gtk::prelude::ObjectExt;
-use gtk::glib;
-use glib::translate::IntoGlib;
+use gtk::prelude::{ObjectExt, StaticType};
+use gtk::{gio, glib};
+use glib::{clone, translate::IntoGlib};
+use glib::subclass::Signal;
 
 use strum::{EnumProperty, FromRepr};
 use indexmap::IndexMap;
 use serde_json::{json, Map as JsonMap, Value as JsonValue};
 
+use crate::filter_rule::FilterRule;
+use crate::filter_set_object::FilterSetObject;
+use crate::rsync_error::RsyncExitStatus;
+use crate::rsync_process::{RsyncRetry, RsyncWatchdog, RSYNC_PROGRAM, RSYNC_PROGRAM_ENV};
+
 //------------------------------------------------------------------------------
 // ENUM: CheckMode
 //------------------------------------------------------------------------------
@@ -152,6 +159,42 @@ mod imp {
         partial: Cell<bool>,
         #[property(get, set, default = false, construct)]
         backup: Cell<bool>,
+
+        #[property(get, set, default = "", construct)]
+        success_codes: RefCell<String>,
+        #[property(get, set, default = "24", construct)]
+        warning_codes: RefCell<String>,
+
+        #[property(get, set, default = 1, minimum = 1, maximum = 100, construct)]
+        retry_attempts: Cell<u32>,
+        #[property(get, set, default = 30, maximum = 3600, construct)]
+        retry_delay: Cell<u32>,
+        #[property(get, set, default = "10, 12, 30, 35", construct)]
+        retry_codes: RefCell<String>,
+
+        #[property(get, set, default = 0, maximum = 86400, construct)]
+        io_timeout: Cell<u32>,
+        #[property(get, set, default = 0, maximum = 86400, construct)]
+        connect_timeout: Cell<u32>,
+        #[property(get, set, default = 300, maximum = 86400, construct)]
+        stall_timeout: Cell<u32>,
+        #[property(get, set, default = false, construct)]
+        stall_terminate: Cell<bool>,
+
+        #[property(get, set, default = "", construct)]
+        rsync_path: RefCell<String>,
+
+        #[property(get, set, default = "")]
+        parent: RefCell<String>,
+        #[property(get, set)]
+        overrides: RefCell<Vec<String>>,
+
+        pub(super) parent_profile: RefCell<Option<super::ProfileObject>>,
+        pub(super) parent_handler: RefCell<Option<glib::SignalHandlerId>>,
+        pub(super) override_handler: RefCell<Option<glib::SignalHandlerId>>,
+        pub(super) syncing: Cell<bool>,
+
+        pub(super) filter_set_model: RefCell<Option<gio::ListStore>>,
     }
 
     //---------------------------------------
@@ -164,7 +207,34 @@ mod imp {
     }
 
     #[glib::derived_properties]
-    impl ObjectImpl for ProfileObject {}
+    impl ObjectImpl for ProfileObject {
+        //---------------------------------------
+        // Signals
+        //---------------------------------------
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("inheritance-changed")
+                        .build(),
+                ]
+            })
+        }
+
+        //---------------------------------------
+        // Constructor
+        //---------------------------------------
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let obj = self.obj();
+
+            // Overrides property notify signal
+            obj.connect_overrides_notify(|profile| {
+                profile.emit_by_name::<()>("inheritance-changed", &[]);
+            });
+        }
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -187,44 +257,73 @@ impl ProfileObject {
     //---------------------------------------
     // From json function
     //---------------------------------------
-    pub fn from_json(name: &str, json_value: &JsonValue) -> Option<Self> {
-        let obj = Self::new(name);
+    pub fn from_json(name: &str, json_value: &JsonValue, diagnostics: &mut Vec<String>) -> Option<Self> {
+        let Some(json_map) = json_value.as_object() else {
+            diagnostics.push(format!("Profile \"{name}\": invalid profile data ignored"));
 
-        let json_map = json_value.as_object()?;
+            return None;
+        };
+
+        let obj = Self::new(name);
 
         for (key, value) in json_map {
-            if obj.has_property(key) {
-                match value {
-                    JsonValue::Array(v) => {
-                        let vec: Vec<String> = v.iter()
-                            .filter_map(|value| value.as_str().map(ToOwned::to_owned))
-                            .collect();
-
-                        obj.set_property(key, vec);
-                    }
-                    JsonValue::String(s) => {
-                        obj.set_property(key, s);
-                    },
-                    JsonValue::Number(i) => {
-                        if key == "check-mode" {
-                            let mode = i.as_u64()
-                                .and_then(|i| CheckMode::from_repr(i as u32))
-                                .unwrap_or_default();
-
-                            obj.set_property(key, mode);
-                        } else if key == "recurse-mode" {
-                            let mode = i.as_u64()
-                                .and_then(|i| RecurseMode::from_repr(i as u32))
-                                .unwrap_or_default();
-
-                            obj.set_property(key, mode);
-                        }
-                    },
-                    JsonValue::Bool(b) => {
-                        obj.set_property(key, b);
-                    }
-                    _ => {}
+            // Report unknown properties
+            let Some(value_type) = obj.find_property(key)
+                .filter(|prop| prop.name() != "name")
+                .map(|prop| prop.value_type()) else {
+                    diagnostics.push(format!("Profile \"{name}\": unknown option \"{key}\" ignored"));
+
+                    continue;
+                };
+
+            // Set property if value type matches
+            let valid = match value {
+                JsonValue::Array(v) if value_type == Vec::<String>::static_type() => {
+                    let vec: Option<Vec<String>> = v.iter()
+                        .map(|value| value.as_str().map(ToOwned::to_owned))
+                        .collect();
+
+                    vec.map(|vec| obj.set_property(key, vec))
+                        .is_some()
+                }
+                JsonValue::String(s) if value_type == String::static_type() => {
+                    obj.set_property(key, s);
+
+                    true
+                },
+                JsonValue::Number(i) if value_type == CheckMode::static_type() => {
+                    i.as_u64()
+                        .and_then(|i| CheckMode::from_repr(u32::try_from(i).ok()?))
+                        .map(|mode| obj.set_property(key, mode))
+                        .is_some()
+                },
+                JsonValue::Number(i) if value_type == RecurseMode::static_type() => {
+                    i.as_u64()
+                        .and_then(|i| RecurseMode::from_repr(u32::try_from(i).ok()?))
+                        .map(|mode| obj.set_property(key, mode))
+                        .is_some()
+                },
+                JsonValue::Number(i) if value_type == u32::static_type() => {
+                    i.as_u64()
+                        .and_then(|i| u32::try_from(i).ok())
+                        .filter(|&i| obj.find_property(key).is_some_and(|prop| {
+                            prop.downcast_ref::<glib::ParamSpecUInt>()
+                                .is_some_and(|prop| (prop.minimum()..=prop.maximum()).contains(&i))
+                        }))
+                        .map(|i| obj.set_property(key, i))
+                        .is_some()
+                },
+                JsonValue::Bool(b) if value_type == bool::static_type() => {
+                    obj.set_property(key, b);
+
+                    true
                 }
+                _ => false
+            };
+
+            // Report invalid values
+            if !valid {
+                diagnostics.push(format!("Profile \"{name}\": invalid value {value} for option \"{key}\" ignored"));
             }
         }
 
@@ -247,6 +346,8 @@ impl ProfileObject {
                     json!(s)
                 } else if let Ok(b) = value.get::<bool>() {
                     json!(b)
+                } else if let Ok(i) = value.get::<u32>() {
+                    json!(i)
                 } else if let Ok(mode) = value.get::<CheckMode>() {
                     json!(mode.value())
                 } else if let Ok(mode) = value.get::<RecurseMode>() {
@@ -272,15 +373,25 @@ impl ProfileObject {
             .property("name", name)
             .build();
 
-        for property in self.list_properties() {
+        dup_obj.assign(self);
+
+        dup_obj
+    }
+
+    //---------------------------------------
+    // Assign function
+    //---------------------------------------
+    pub fn assign(&self, other: &Self) {
+        for property in other.list_properties() {
             let nick = property.nick();
 
-            if nick != "name" {
-                dup_obj.set_property_from_value(nick, &self.property_value(nick));
+            if nick != "name" && nick != "overrides" {
+                self.set_property_from_value(nick, &other.property_value(nick));
             }
         }
 
-        dup_obj
+        // Set overrides last (setting properties may add overrides)
+        self.set_overrides(other.overrides());
     }
 
     //---------------------------------------
@@ -290,10 +401,289 @@ impl ProfileObject {
         for property in self.list_properties() {
             let nick = property.nick();
 
-            if nick != "name" {
+            if nick != "name" && nick != "overrides" {
                 self.set_property_from_value(nick, property.default_value());
             }
         }
+
+        self.set_overrides(Vec::<String>::new());
+    }
+
+    //---------------------------------------
+    // Inheritable helper function
+    //---------------------------------------
+    fn is_inheritable(nick: &str) -> bool {
+        !matches!(nick, "name" | "source" | "destination" | "parent" | "overrides")
+    }
+
+    //---------------------------------------
+    // Parent profile function
+    //---------------------------------------
+    pub fn parent_profile(&self) -> Option<Self> {
+        self.imp().parent_profile.borrow().clone()
+    }
+
+    //---------------------------------------
+    // Is inherited function
+    //---------------------------------------
+    pub fn is_inherited(&self, nick: &str) -> bool {
+        self.imp().parent_profile.borrow().is_some()
+            && Self::is_inheritable(nick)
+            && !self.overrides().iter().any(|o| o == nick)
+    }
+
+    //---------------------------------------
+    // Is overridden function
+    //---------------------------------------
+    pub fn is_overridden(&self, nick: &str) -> bool {
+        self.imp().parent_profile.borrow().is_some()
+            && Self::is_inheritable(nick)
+            && self.overrides().iter().any(|o| o == nick)
+    }
+
+    //---------------------------------------
+    // Inherits from function
+    //---------------------------------------
+    pub fn inherits_from(&self, ancestor: &Self) -> bool {
+        let mut parent = self.parent_profile();
+
+        while let Some(profile) = parent {
+            if &profile == ancestor {
+                return true;
+            }
+
+            parent = profile.parent_profile();
+        }
+
+        false
+    }
+
+    //---------------------------------------
+    // Sync property helper function
+    //---------------------------------------
+    fn sync_property(&self, parent: &Self, nick: &str) {
+        let imp = self.imp();
+
+        imp.syncing.set(true);
+        self.set_property_from_value(nick, &parent.property_value(nick));
+        imp.syncing.set(false);
+    }
+
+    //---------------------------------------
+    // Set parent profile function
+    //---------------------------------------
+    pub fn set_parent_profile(&self, parent: Option<&Self>) {
+        let imp = self.imp();
+
+        if imp.parent_profile.borrow().as_ref() == parent {
+            return;
+        }
+
+        // Disconnect signal handlers
+        if let Some(old_parent) = imp.parent_profile.take()
+            && let Some(handler) = imp.parent_handler.take() {
+                old_parent.disconnect(handler);
+            }
+
+        if let Some(handler) = imp.override_handler.take() {
+            self.disconnect(handler);
+        }
+
+        imp.parent_profile.replace(parent.cloned());
+
+        if let Some(parent) = parent {
+            // Sync inherited properties from parent
+            for property in self.list_properties() {
+                let nick = property.nick();
+
+                if self.is_inherited(nick) {
+                    self.sync_property(parent, nick);
+                }
+            }
+
+            // Parent property notify signal (propagate changes to child)
+            let parent_handler = parent.connect_notify_local(None, clone!(
+                #[weak(rename_to = profile)] self,
+                move |parent, pspec| {
+                    let nick = pspec.name();
+
+                    if nick == "name" {
+                        profile.set_parent(parent.name());
+                    } else if profile.is_inherited(nick) {
+                        profile.sync_property(parent, nick);
+                    }
+                }
+            ));
+
+            imp.parent_handler.replace(Some(parent_handler));
+
+            // Property notify signal (track overridden properties)
+            let override_handler = self.connect_notify_local(None, |profile, pspec| {
+                let nick = pspec.name();
+
+                if !profile.imp().syncing.get() && profile.is_inherited(nick) {
+                    let mut overrides = profile.overrides();
+                    overrides.push(nick.to_owned());
+
+                    profile.set_overrides(overrides);
+                }
+            });
+
+            imp.override_handler.replace(Some(override_handler));
+        }
+
+        self.emit_by_name::<()>("inheritance-changed", &[]);
+    }
+
+    //---------------------------------------
+    // Set filter set model function
+    //---------------------------------------
+    pub fn set_filter_set_model(&self, model: Option<&gio::ListStore>) {
+        self.imp().filter_set_model.replace(model.cloned());
+    }
+
+    //---------------------------------------
+    // Expanded filters function
+    //---------------------------------------
+    pub fn expanded_filters(&self) -> Vec<String> {
+        let filters = self.effective_value("filters")
+            .get::<Vec<String>>()
+            .unwrap_or_default();
+
+        FilterSetObject::expand_filters(self.imp().filter_set_model.borrow().as_ref(), &filters)
+    }
+
+    //---------------------------------------
+    // Replace filter function
+    //---------------------------------------
+    // Replaces own filter entries (inherited filters follow the template)
+    pub fn replace_filter(&self, old_filter: &str, new_filter: &str) {
+        if self.is_inherited("filters") || !self.filters().iter().any(|filter| filter == old_filter) {
+            return;
+        }
+
+        let filters: Vec<String> = self.filters().into_iter()
+            .map(|filter| if filter == old_filter { new_filter.to_owned() } else { filter })
+            .collect();
+
+        self.set_filters(filters);
+    }
+
+    //---------------------------------------
+    // Invalid filters function
+    //---------------------------------------
+    // Filters that cannot be passed to rsync (including references to missing filter sets)
+    pub fn invalid_filters(&self) -> Vec<String> {
+        let filters = self.effective_value("filters")
+            .get::<Vec<String>>()
+            .unwrap_or_default();
+
+        FilterSetObject::unresolved_references(self.imp().filter_set_model.borrow().as_ref(), &filters).into_iter()
+            .map(|reference| format!("{reference} (filter set not found)"))
+            .chain(self.expanded_filters().into_iter().filter(|filter| FilterRule::parse(filter).is_none()))
+            .collect()
+    }
+
+    //---------------------------------------
+    // Revert function
+    //---------------------------------------
+    pub fn revert(&self, nick: &str) {
+        let overrides: Vec<String> = self.overrides().into_iter()
+            .filter(|o| o != nick)
+            .collect();
+
+        self.set_overrides(overrides);
+
+        if let Some(parent) = self.parent_profile() {
+            self.sync_property(&parent, nick);
+        }
+    }
+
+    //---------------------------------------
+    // Effective value function
+    //---------------------------------------
+    fn effective_value(&self, nick: &str) -> glib::Value {
+        match self.parent_profile() {
+            Some(parent) if self.is_inherited(nick) => parent.effective_value(nick),
+            _ => self.property_value(nick)
+        }
+    }
+
+    //---------------------------------------
+    // Exit status function
+    //---------------------------------------
+    pub fn exit_status(&self, code: i32) -> RsyncExitStatus {
+        let success_codes = self.effective_value("success-codes")
+            .get::<String>()
+            .unwrap_or_default();
+
+        let warning_codes = self.effective_value("warning-codes")
+            .get::<String>()
+            .unwrap_or_default();
+
+        RsyncExitStatus::from_code(code, &success_codes, &warning_codes)
+    }
+
+    //---------------------------------------
+    // Retry function
+    //---------------------------------------
+    pub fn retry(&self) -> RsyncRetry {
+        let retry_codes = self.effective_value("retry-codes")
+            .get::<String>()
+            .unwrap_or_default();
+
+        RsyncRetry {
+            max_attempts: self.effective_value("retry-attempts").get::<u32>().unwrap_or(1),
+            delay: self.effective_value("retry-delay").get::<u32>().unwrap_or_default(),
+            codes: RsyncExitStatus::parse_codes(&retry_codes)
+        }
+    }
+
+    //---------------------------------------
+    // Watchdog function
+    //---------------------------------------
+    pub fn watchdog(&self) -> RsyncWatchdog {
+        RsyncWatchdog {
+            timeout: self.effective_value("stall-timeout").get::<u32>().unwrap_or_default(),
+            terminate: self.effective_value("stall-terminate").get::<bool>().unwrap_or_default()
+        }
+    }
+
+    //---------------------------------------
+    // Rsync program function
+    //---------------------------------------
+    // Profile setting, then environment variable, then rsync from PATH
+    pub fn rsync_program(&self) -> String {
+        let rsync_path = self.effective_value("rsync-path")
+            .get::<String>()
+            .unwrap_or_default();
+
+        if !rsync_path.trim().is_empty() {
+            return rsync_path.trim().to_owned();
+        }
+
+        Self::default_program()
+    }
+
+    //---------------------------------------
+    // Default program function
+    //---------------------------------------
+    // Environment variable, then rsync from PATH
+    pub fn default_program() -> String {
+        std::env::var(RSYNC_PROGRAM_ENV)
+            .ok()
+            .filter(|program| !program.is_empty())
+            .unwrap_or_else(|| RSYNC_PROGRAM.to_owned())
+    }
+
+    //---------------------------------------
+    // Option switch function
+    //---------------------------------------
+    // Command line switch of boolean option
+    pub fn option_switch(nick: &str) -> Option<&'static str> {
+        BOOLEAN_OPTIONS.iter()
+            .find(|(name, _)| *name == nick)
+            .map(|(_, (arg, _))| *arg)
     }
 
     //---------------------------------------
@@ -301,11 +691,19 @@ impl ProfileObject {
     //---------------------------------------
     pub fn options(&self, quoted: bool) -> Vec<String> {
         // Check mode
-        let mut options: Vec<String> = self.check_mode().switch()
+        let check_mode = self.effective_value("check-mode")
+            .get::<CheckMode>()
+            .unwrap_or_default();
+
+        let mut options: Vec<String> = check_mode.switch()
             .map_or_else(Vec::new, |mode| vec![mode.to_owned()]);
 
         // Recurse mode
-        if let Some(mode) = self.recurse_mode().switches() {
+        let recurse_mode = self.effective_value("recurse-mode")
+            .get::<RecurseMode>()
+            .unwrap_or_default();
+
+        if let Some(mode) = recurse_mode.switches() {
             let switches: Vec<String> = mode.split(' ')
                 .map(ToOwned::to_owned)
                 .collect();
@@ -316,7 +714,7 @@ impl ProfileObject {
         // Advanced options
         let advanced: Vec<String> = IndexMap::from(BOOLEAN_OPTIONS).iter()
             .filter_map(|(&nick, &(arg, off_arg))| {
-                let value = self.property_value(nick)
+                let value = self.effective_value(nick)
                     .get::<bool>()
                     .ok()?;
 
@@ -328,17 +726,52 @@ impl ProfileObject {
 
         options.extend_from_slice(&advanced);
 
-        // Filters
-        let replace = if quoted { "\"" } else { "" };
+        // Timeouts
+        let timeouts: Vec<String> = [("io-timeout", "--timeout"), ("connect-timeout", "--contimeout")].iter()
+            .filter_map(|&(nick, arg)| {
+                let value = self.effective_value(nick)
+                    .get::<u32>()
+                    .ok()?;
 
-        if !self.filters().is_empty() {
-            let mut filters = self.filters().into_iter()
-                .map(|filter| filter.replace(['\'', '"'], replace))
-                .collect::<Vec<String>>();
+                (value > 0).then(|| format!("{arg}={value}"))
+            })
+            .collect();
 
-            options.append(&mut filters);
-        }
+        options.extend_from_slice(&timeouts);
+
+        // Filters (with filter set references expanded)
+        let mut filters: Vec<String> = self.expanded_filters().iter()
+            .filter_map(|filter| FilterRule::parse(filter))
+            .map(|rule| rule.arg(quoted))
+            .collect();
+
+        options.append(&mut filters);
 
         options
     }
 }
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_filters_reports_missing_sets() {
+        let model = gio::ListStore::new::<FilterSetObject>();
+        model.append(&FilterSetObject::new("build", &[String::from("- target/")]));
+
+        let profile = ProfileObject::new("Test");
+        profile.set_filter_set_model(Some(&model));
+        profile.set_filters(vec![String::from("@build"), String::from("@deleted"), String::from("- *.o")]);
+
+        assert_eq!(profile.invalid_filters(), ["@deleted (filter set not found)"]);
+
+        // Set removed after the profile was configured
+        model.remove_all();
+
+        assert_eq!(profile.invalid_filters(), ["@build (filter set not found)", "@deleted (filter set not found)"]);
+    }
+}
@@ -0,0 +1,471 @@
+use std::sync::LazyLock;
+
+use strum::EnumProperty;
+use regex::Regex;
+
+use crate::utils::case;
+
+//------------------------------------------------------------------------------
+// ENUM: RsyncErrorCategory
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy, Hash, EnumProperty)]
+pub enum RsyncErrorCategory {
+    #[strum(props(Label="Usage Errors", Desc="Rsync was called with invalid options or filter rules", Fix="Check the advanced options and filter rules of the profile"))]
+    Usage,
+    #[strum(props(Label="Protocol Errors", Desc="The rsync versions on both ends are incompatible", Fix="Update rsync on the local and remote host"))]
+    Protocol,
+    #[strum(props(Label="Unsupported Actions", Desc="An option is not supported by the rsync version on one end", Fix="Disable options not supported by the remote rsync or update rsync"))]
+    Unsupported,
+    #[strum(props(Label="Missing Files", Desc="The source or destination could not be found or opened", Fix="Check that the source and destination paths exist and are accessible"))]
+    FileSelection,
+    #[strum(props(Label="Permission Errors", Desc="Access to some files or folders was denied", Fix="Check file ownership and permissions, or disable preserving owner and group"))]
+    Permission,
+    #[strum(props(Label="Disk Full", Desc="There is no space left on the destination device", Fix="Free up space on the destination or exclude large files"))]
+    NoSpace,
+    #[strum(props(Label="Read-Only Destination", Desc="The destination file system is mounted read-only", Fix="Remount the destination with write access or choose another destination"))]
+    ReadOnly,
+    #[strum(props(Label="Vanished Files", Desc="Some source files were deleted or renamed while being transferred", Fix="Run the transfer again, or exclude temporary files that change during the transfer"))]
+    Vanished,
+    #[strum(props(Label="Partial Transfer", Desc="Some files or attributes were not transferred", Fix="Review the errors in the rsync output and run the transfer again"))]
+    Partial,
+    #[strum(props(Label="Authentication Errors", Desc="The remote host could not be authenticated or rejected the login", Fix="Connect once with ssh to verify the host key, and check your ssh keys or password"))]
+    Authentication,
+    #[strum(props(Label="Connection Errors", Desc="The connection to the remote host failed or was interrupted", Fix="Check the network connection and that the remote host is reachable"))]
+    Connection,
+    #[strum(props(Label="Timeouts", Desc="No data was received within the timeout period", Fix="Check the network connection or increase the I/O timeout"))]
+    Timeout,
+    #[strum(props(Label="File I/O Errors", Desc="Reading or writing a file failed", Fix="Check the source and destination disks for errors"))]
+    FileIo,
+    #[strum(props(Label="Resource Errors", Desc="Rsync ran out of memory or could not start a process", Fix="Close other applications or transfer fewer files at once"))]
+    Resource,
+    #[strum(props(Label="Deletion Limit", Desc="The maximum number of deletions was reached", Fix="Check the deleted files and increase the deletion limit if intended"))]
+    MaxDelete,
+    #[strum(props(Label="Terminated", Desc="The transfer was terminated", Fix=""))]
+    Terminated,
+    #[default]
+    #[strum(props(Label="Other Errors", Desc="Rsync reported an unknown error", Fix="Review the errors in the rsync output"))]
+    Other,
+}
+
+impl RsyncErrorCategory {
+    pub fn label(self) -> &'static str {
+        self.get_str("Label").unwrap_or_default()
+    }
+
+    pub fn desc(self) -> &'static str {
+        self.get_str("Desc").unwrap_or_default()
+    }
+
+    pub fn fix(self) -> &'static str {
+        self.get_str("Fix").unwrap_or_default()
+    }
+}
+
+//------------------------------------------------------------------------------
+// ENUM: RsyncExitStatus
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RsyncExitStatus {
+    #[default]
+    Success,
+    Warning,
+    Error,
+}
+
+impl RsyncExitStatus {
+    //---------------------------------------
+    // From code function
+    //---------------------------------------
+    pub fn from_code(code: i32, success_codes: &str, warning_codes: &str) -> Self {
+        if code == 0 || Self::parse_codes(success_codes).contains(&code) {
+            Self::Success
+        } else if Self::parse_codes(warning_codes).contains(&code) {
+            Self::Warning
+        } else {
+            Self::Error
+        }
+    }
+
+    //---------------------------------------
+    // Parse codes function
+    //---------------------------------------
+    // Exit code lists are separated by commas and/or spaces
+    pub fn parse_codes(codes: &str) -> Vec<i32> {
+        codes.split([',', ' '])
+            .filter_map(|code| code.trim().parse::<i32>().ok())
+            .collect()
+    }
+}
+
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+// Rsync exit codes (see rsync man page, EXIT VALUES)
+const EXIT_CODES: [(i32, RsyncErrorCategory, &str); 22] = [
+    (1, RsyncErrorCategory::Usage, "Syntax or usage error"),
+    (2, RsyncErrorCategory::Protocol, "Protocol incompatibility"),
+    (3, RsyncErrorCategory::FileSelection, "Errors selecting input/output files, dirs"),
+    (4, RsyncErrorCategory::Unsupported, "Requested action not supported"),
+    (5, RsyncErrorCategory::Connection, "Error starting client-server protocol"),
+    (6, RsyncErrorCategory::FileIo, "Daemon unable to append to log-file"),
+    (10, RsyncErrorCategory::Connection, "Error in socket I/O"),
+    (11, RsyncErrorCategory::FileIo, "Error in file I/O"),
+    (12, RsyncErrorCategory::Connection, "Error in rsync protocol data stream"),
+    (13, RsyncErrorCategory::Other, "Errors with program diagnostics"),
+    (14, RsyncErrorCategory::Resource, "Error in IPC code"),
+    (15, RsyncErrorCategory::Resource, "Sibling process crashed"),
+    (16, RsyncErrorCategory::Resource, "Sibling process terminated abnormally"),
+    (19, RsyncErrorCategory::Terminated, "Received SIGUSR1"),
+    (20, RsyncErrorCategory::Terminated, "Terminated by user"),
+    (21, RsyncErrorCategory::Resource, "Some error returned by waitpid()"),
+    (22, RsyncErrorCategory::Resource, "Error allocating core memory buffers"),
+    (23, RsyncErrorCategory::Partial, "Partial transfer due to error"),
+    (24, RsyncErrorCategory::Vanished, "Partial transfer due to vanished source files"),
+    (25, RsyncErrorCategory::MaxDelete, "The --max-delete limit stopped deletions"),
+    (30, RsyncErrorCategory::Timeout, "Timeout in data send/receive"),
+    (35, RsyncErrorCategory::Timeout, "Timeout waiting for daemon connection"),
+];
+
+// Stderr patterns (checked in order, case insensitive)
+const ERROR_PATTERNS: [(&str, RsyncErrorCategory); 17] = [
+    ("host key verification failed", RsyncErrorCategory::Authentication),
+    ("permission denied (publickey", RsyncErrorCategory::Authentication),
+    ("auth failed", RsyncErrorCategory::Authentication),
+    ("permission denied", RsyncErrorCategory::Permission),
+    ("operation not permitted", RsyncErrorCategory::Permission),
+    ("no space left on device", RsyncErrorCategory::NoSpace),
+    ("disk quota exceeded", RsyncErrorCategory::NoSpace),
+    ("read-only file system", RsyncErrorCategory::ReadOnly),
+    ("file has vanished", RsyncErrorCategory::Vanished),
+    ("connection refused", RsyncErrorCategory::Connection),
+    ("could not resolve hostname", RsyncErrorCategory::Connection),
+    ("connection unexpectedly closed", RsyncErrorCategory::Connection),
+    ("broken pipe", RsyncErrorCategory::Connection),
+    ("timed out", RsyncErrorCategory::Timeout),
+    ("timeout", RsyncErrorCategory::Timeout),
+    ("no such file or directory", RsyncErrorCategory::FileSelection),
+    ("input/output error", RsyncErrorCategory::FileIo),
+];
+
+//------------------------------------------------------------------------------
+// STRUCT: RsyncError
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone)]
+pub struct RsyncError {
+    pub code: i32,
+    pub category: RsyncErrorCategory,
+    pub message: String
+}
+
+impl RsyncError {
+    //---------------------------------------
+    // Classify function
+    //---------------------------------------
+    pub fn classify(code: i32, errors: &[String]) -> Self {
+        let (code_category, code_message) = Self::exit_code(code);
+
+        // Use stderr category for generic exit codes
+        let category = match code_category {
+            RsyncErrorCategory::Partial | RsyncErrorCategory::Other | RsyncErrorCategory::FileSelection => {
+                errors.iter()
+                    .find_map(|line| Self::classify_line(line))
+                    .filter(|&category| category != RsyncErrorCategory::Partial)
+                    .unwrap_or(code_category)
+            }
+            category => category
+        };
+
+        // Get error message (usage errors are reported in first line)
+        let message = match code {
+            20 => Some(code_message.to_owned()),
+            1 => Self::extract_error(errors.first())
+                .or_else(|| Self::extract_error(errors.last())),
+            _ => Self::extract_error(errors.last())
+        }
+        .unwrap_or_else(|| code_message.to_owned());
+
+        Self {
+            code,
+            category,
+            message
+        }
+    }
+
+    //---------------------------------------
+    // Exit code function
+    //---------------------------------------
+    pub fn exit_code(code: i32) -> (RsyncErrorCategory, &'static str) {
+        EXIT_CODES.iter()
+            .find(|(c, _, _)| *c == code)
+            .map_or((RsyncErrorCategory::Other, "Unknown error"), |&(_, category, message)| (category, message))
+    }
+
+    //---------------------------------------
+    // Classify line function
+    //---------------------------------------
+    pub fn classify_line(line: &str) -> Option<RsyncErrorCategory> {
+        static CODE_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"\(code (?P<code>\d+)\)")
+                .expect("Failed to compile Regex")
+        });
+
+        let line = line.to_ascii_lowercase();
+
+        ERROR_PATTERNS.iter()
+            .find(|(pattern, _)| line.contains(pattern))
+            .map(|&(_, category)| category)
+            .or_else(|| {
+                let code = CODE_EXPR.captures(&line)?
+                    .name("code")?
+                    .as_str()
+                    .parse::<i32>()
+                    .ok()?;
+
+                Some(Self::exit_code(code).0)
+            })
+    }
+
+    //---------------------------------------
+    // Group errors function
+    //---------------------------------------
+    pub fn group_errors(errors: &[String]) -> Vec<(RsyncErrorCategory, Vec<String>)> {
+        let mut groups: Vec<(RsyncErrorCategory, Vec<String>)> = vec![];
+
+        for error in errors {
+            let category = Self::classify_line(error).unwrap_or_default();
+
+            if let Some((_, group)) = groups.iter_mut().find(|(c, _)| *c == category) {
+                group.push(error.clone());
+            } else {
+                groups.push((category, vec![error.clone()]));
+            }
+        }
+
+        groups
+    }
+
+    //---------------------------------------
+    // Vanished files function
+    //---------------------------------------
+    pub fn vanished_files(errors: &[String]) -> Vec<String> {
+        static EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r#"(?i)file has vanished: "(?P<path>.+)"(?: \(in .+\))?$"#)
+                .expect("Failed to compile Regex")
+        });
+
+        errors.iter()
+            .filter_map(|error| EXPR.captures(error)?.name("path"))
+            .map(|m| m.as_str().to_owned())
+            .collect()
+    }
+
+    //---------------------------------------
+    // Extract error helper function
+    //---------------------------------------
+    fn extract_error(msg: Option<&String>) -> Option<String> {
+        static EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"^(?P<err>[^(]*).*")
+                .expect("Failed to compile Regex")
+        });
+
+        EXPR.captures(msg?)?
+            .name("err")
+            .map(|m| {
+                let s = m.as_str().trim()
+                    .trim_end_matches('.')
+                    .replace("Rsync error: ", "")
+                    .replace("Rsync warning: ", "");
+
+                case::capitalize_first(&s)
+            })
+            .filter(|s| !s.is_empty())
+    }
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(errors: &[&str]) -> Vec<String> {
+        errors.iter().map(|&error| error.to_owned()).collect()
+    }
+
+    #[test]
+    fn parse_codes() {
+        let codes: [(&str, &[i32]); 6] = [
+            ("", &[]),
+            ("24", &[24]),
+            ("23,24", &[23, 24]),
+            ("23 24", &[23, 24]),
+            (" 23, 24 ,25", &[23, 24, 25]),
+            ("23, abc, 24", &[23, 24])
+        ];
+
+        for (input, expected) in codes {
+            assert_eq!(RsyncExitStatus::parse_codes(input), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn exit_status_from_code() {
+        let codes = [
+            (0, "", "", RsyncExitStatus::Success),
+            (24, "24", "", RsyncExitStatus::Success),
+            (24, "", "23, 24", RsyncExitStatus::Warning),
+            (23, "24", "23", RsyncExitStatus::Warning),
+            (23, "24", "", RsyncExitStatus::Error),
+            (1, "", "", RsyncExitStatus::Error)
+        ];
+
+        for (code, success, warning, expected) in codes {
+            assert_eq!(RsyncExitStatus::from_code(code, success, warning), expected, "{code}");
+        }
+    }
+
+    #[test]
+    fn exit_code() {
+        let codes = [
+            (1, RsyncErrorCategory::Usage),
+            (2, RsyncErrorCategory::Protocol),
+            (3, RsyncErrorCategory::FileSelection),
+            (4, RsyncErrorCategory::Unsupported),
+            (10, RsyncErrorCategory::Connection),
+            (11, RsyncErrorCategory::FileIo),
+            (20, RsyncErrorCategory::Terminated),
+            (22, RsyncErrorCategory::Resource),
+            (23, RsyncErrorCategory::Partial),
+            (24, RsyncErrorCategory::Vanished),
+            (25, RsyncErrorCategory::MaxDelete),
+            (30, RsyncErrorCategory::Timeout),
+            (35, RsyncErrorCategory::Timeout),
+            (99, RsyncErrorCategory::Other)
+        ];
+
+        for (code, category) in codes {
+            assert_eq!(RsyncError::exit_code(code).0, category, "{code}");
+        }
+
+        assert_eq!(RsyncError::exit_code(99).1, "Unknown error");
+    }
+
+    #[test]
+    fn classify_line() {
+        let errors = [
+            ("Host key verification failed.", Some(RsyncErrorCategory::Authentication)),
+            ("user@host: Permission denied (publickey,password).", Some(RsyncErrorCategory::Authentication)),
+            ("@ERROR: auth failed on module backup", Some(RsyncErrorCategory::Authentication)),
+            (r#"rsync: [sender] opendir "/root" failed: Permission denied (13)"#, Some(RsyncErrorCategory::Permission)),
+            (r#"rsync: chown "/dst/file" failed: Operation not permitted (1)"#, Some(RsyncErrorCategory::Permission)),
+            (r#"rsync: write failed on "/dst/file": No space left on device (28)"#, Some(RsyncErrorCategory::NoSpace)),
+            (r#"rsync: write failed on "/dst/file": Disk quota exceeded (122)"#, Some(RsyncErrorCategory::NoSpace)),
+            (r#"rsync: mkstemp "/dst/.file" failed: Read-only file system (30)"#, Some(RsyncErrorCategory::ReadOnly)),
+            (r#"file has vanished: "/src/tmp.log""#, Some(RsyncErrorCategory::Vanished)),
+            ("ssh: connect to host host port 22: Connection refused", Some(RsyncErrorCategory::Connection)),
+            ("ssh: Could not resolve hostname host: Name or service not known", Some(RsyncErrorCategory::Connection)),
+            ("rsync: connection unexpectedly closed (0 bytes received so far) [sender]", Some(RsyncErrorCategory::Connection)),
+            ("rsync: [sender] write error: Broken pipe (32)", Some(RsyncErrorCategory::Connection)),
+            ("ssh: connect to host host port 22: Connection timed out", Some(RsyncErrorCategory::Timeout)),
+            ("[sender] io timeout after 30 seconds -- exiting", Some(RsyncErrorCategory::Timeout)),
+            (r#"rsync: change_dir "/missing" failed: No such file or directory (2)"#, Some(RsyncErrorCategory::FileSelection)),
+            (r#"rsync: read errors mapping "/src/file": Input/output error (5)"#, Some(RsyncErrorCategory::FileIo)),
+            ("rsync error: some files/attrs were not transferred (see previous errors) (code 23) at main.c(1338) [sender=3.2.7]", Some(RsyncErrorCategory::Partial)),
+            ("rsync warning: some files vanished before they could be transferred (code 24) at main.c(1338) [sender=3.2.7]", Some(RsyncErrorCategory::Vanished)),
+            ("rsync error: syntax or usage error (code 1) at main.c(1795) [client=3.2.7]", Some(RsyncErrorCategory::Usage)),
+            ("rsync error: unknown failure (code 99)", Some(RsyncErrorCategory::Other)),
+            ("sending incremental file list", None)
+        ];
+
+        for (line, category) in errors {
+            assert_eq!(RsyncError::classify_line(line), category, "{line}");
+        }
+    }
+
+    #[test]
+    fn classify() {
+        // Stderr lines are capitalized by RsyncProcess
+        let vanished = lines(&[
+            r#"file has vanished: "/src/tmp.log""#,
+            "Rsync warning: some files vanished before they could be transferred (code 24) at main.c(1338) [sender=3.2.7]"
+        ]);
+
+        let error = RsyncError::classify(24, &vanished);
+
+        assert_eq!(error.category, RsyncErrorCategory::Vanished);
+        assert_eq!(error.message, "Some files vanished before they could be transferred");
+
+        // Partial transfers take the category of the first specific error
+        let partial = lines(&[
+            r#"Rsync: [sender] opendir "/root" failed: Permission denied (13)"#,
+            "Rsync error: some files/attrs were not transferred (see previous errors) (code 23) at main.c(1338) [sender=3.2.7]"
+        ]);
+
+        let error = RsyncError::classify(23, &partial);
+
+        assert_eq!(error.category, RsyncErrorCategory::Permission);
+        assert_eq!(error.message, "Some files/attrs were not transferred");
+
+        // Partial transfers without specific errors stay partial
+        let error = RsyncError::classify(23, &partial[1..]);
+
+        assert_eq!(error.category, RsyncErrorCategory::Partial);
+
+        // Usage errors are reported in the first line
+        let usage = lines(&[
+            "Rsync: --bogus: unknown option",
+            "Rsync error: syntax or usage error (code 1) at main.c(1795) [client=3.2.7]"
+        ]);
+
+        let error = RsyncError::classify(1, &usage);
+
+        assert_eq!(error.category, RsyncErrorCategory::Usage);
+        assert_eq!(error.message, "Rsync: --bogus: unknown option");
+
+        // Specific exit codes are not overridden by stderr
+        let error = RsyncError::classify(30, &partial);
+
+        assert_eq!(error.category, RsyncErrorCategory::Timeout);
+
+        // Terminated transfers and empty stderr use the exit code message
+        assert_eq!(RsyncError::classify(20, &usage).message, "Terminated by user");
+        assert_eq!(RsyncError::classify(11, &[]).message, "Error in file I/O");
+    }
+
+    #[test]
+    fn group_errors() {
+        let errors = lines(&[
+            r#"rsync: [sender] opendir "/a" failed: Permission denied (13)"#,
+            r#"file has vanished: "/src/tmp.log""#,
+            "some unrecognized message",
+            r#"rsync: [sender] opendir "/b" failed: Permission denied (13)"#
+        ]);
+
+        let groups = RsyncError::group_errors(&errors);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0], (RsyncErrorCategory::Permission, vec![errors[0].clone(), errors[3].clone()]));
+        assert_eq!(groups[1], (RsyncErrorCategory::Vanished, vec![errors[1].clone()]));
+        assert_eq!(groups[2], (RsyncErrorCategory::Other, vec![errors[2].clone()]));
+
+        assert!(RsyncError::group_errors(&[]).is_empty());
+    }
+
+    #[test]
+    fn vanished_files() {
+        let errors = lines(&[
+            r#"file has vanished: "/src/tmp.log""#,
+            r#"file has vanished: "/src/dir/with space.txt" (in backup)"#,
+            r#"File has vanished: "/src/upper.txt""#,
+            r#"rsync: [sender] opendir "/root" failed: Permission denied (13)"#,
+            "rsync warning: some files vanished before they could be transferred (code 24) at main.c(1338) [sender=3.2.7]"
+        ]);
+
+        assert_eq!(RsyncError::vanished_files(&errors), [
+            "/src/tmp.log",
+            "/src/dir/with space.txt",
+            "/src/upper.txt"
+        ]);
+    }
+}
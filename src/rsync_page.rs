@@ -1,4 +1,5 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
 use std::time::Duration;
 
 use adw::subclass::prelude::*;
@@ -10,9 +11,16 @@ use crate::{
     profile_object::ProfileObject,
     stats_table::StatsTable,
     output_window::OutputWindow,
-    rsync_process::{RsyncProcess, RsyncMessages}
+    rsync_process::{RsyncProcess, RsyncMessage, RsyncMessages, RsyncProgress},
+    rsync_error::{RsyncError, RsyncErrorCategory, RsyncExitStatus},
+    utils::convert
 };
 
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+const MAX_SPEED_SAMPLES: usize = 120;
+
 //------------------------------------------------------------------------------
 // MODULE: RsyncPage
 //------------------------------------------------------------------------------
@@ -39,8 +47,24 @@ mod imp {
         #[template_child]
         pub(super) message_label: TemplateChild<gtk::Label>,
         #[template_child]
+        pub(super) hint_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) dropped_label: TemplateChild<gtk::Label>,
+        #[template_child]
         pub(super) progress_bar: TemplateChild<gtk::ProgressBar>,
         #[template_child]
+        pub(super) files_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) eta_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) speed_graph: TemplateChild<gtk::DrawingArea>,
+        #[template_child]
+        pub(super) speed_summary_label: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub(super) file_box: TemplateChild<gtk::Box>,
+        #[template_child]
+        pub(super) file_label: TemplateChild<gtk::Label>,
+        #[template_child]
         pub(super) source_box: TemplateChild<gtk::Box>,
         #[template_child]
         pub(super) source_label: TemplateChild<gtk::Label>,
@@ -62,7 +86,13 @@ mod imp {
         #[template_child]
         pub(super) stop_button: TemplateChild<gtk::Button>,
         #[template_child]
+        pub(super) stop_content: TemplateChild<adw::ButtonContent>,
+        #[template_child]
         pub(super) output_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub(super) live_output_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub(super) retry_button: TemplateChild<gtk::Button>,
 
         #[property(get, set, nullable)]
         profile: RefCell<Option<ProfileObject>>,
@@ -72,6 +102,12 @@ mod imp {
         pub(super) output_window: RefCell<OutputWindow>,
 
         pub(super) binding: RefCell<Option<glib::Binding>>,
+        pub(super) retry_source: RefCell<Option<glib::SourceId>>,
+
+        pub(super) speed_samples: RefCell<VecDeque<f64>>,
+        pub(super) speed_peak: Cell<f64>,
+        pub(super) speed_sum: Cell<f64>,
+        pub(super) speed_count: Cell<u32>,
     }
 
     //---------------------------------------
@@ -103,6 +139,7 @@ mod imp {
             let obj = self.obj();
 
             obj.setup_signals();
+            obj.setup_widgets();
         }
     }
 
@@ -189,10 +226,69 @@ impl RsyncPage {
             }
         ));
 
+        // Rsync process terminating property notify signal
+        rsync_process.connect_terminating_notify(clone!(
+            #[weak] imp,
+            move |process| {
+                if process.terminating() {
+                    imp.stop_content.set_label("Force _Kill");
+                    imp.stop_button.set_tooltip_text(Some("Rsync is terminating, press again to kill it immediately"));
+                } else {
+                    imp.stop_content.set_label("_Terminate");
+                    imp.stop_button.set_tooltip_text(None);
+                }
+            }
+        ));
+
+        // Rsync process stalled property notify signal
+        rsync_process.connect_stalled_notify(clone!(
+            #[weak(rename_to = page)] self,
+            move |process| {
+                let imp = page.imp();
+
+                if process.stalled() {
+                    let (timeout, terminate) = page.profile()
+                        .map(|profile| (profile.stall_timeout(), profile.stall_terminate()))
+                        .unwrap_or_default();
+
+                    imp.message_box.set_css_classes(&["warning", "heading"]);
+                    imp.message_image.set_icon_name(Some("dialog-warning-symbolic"));
+
+                    imp.message_label.set_label(&format!("Transfer stalled: no progress for {timeout}s"));
+
+                    imp.hint_label.set_label(if terminate {
+                        "The source or destination may be unresponsive. Terminating rsync"
+                    } else {
+                        "The source or destination may be unresponsive"
+                    });
+                    imp.hint_label.set_visible(true);
+                } else if process.running() {
+                    imp.message_box.set_css_classes(&[]);
+                    imp.message_image.set_icon_name(Some("rsync-message-symbolic"));
+
+                    imp.hint_label.set_visible(false);
+                }
+            }
+        ));
+
         // Rsync process start signal
         rsync_process.connect_closure("start", false, closure_local!(
-            #[weak] imp,
+            #[weak(rename_to = page)] self,
             move |process: RsyncProcess| {
+                let imp = page.imp();
+
+                page.stop_retry_countdown();
+
+                let (source, destination) = page.profile()
+                    .map(|profile| (profile.source(), profile.destination()))
+                    .unwrap_or_default();
+
+                imp.output_window.borrow().set_roots(&source, &destination);
+                imp.output_window.borrow().start_streaming();
+
+                imp.message_box.set_css_classes(&[]);
+                imp.message_image.set_icon_name(Some("rsync-message-symbolic"));
+
                 glib::timeout_add_local_once(Duration::from_millis(150), clone!(
                     #[weak] imp,
                     move || {
@@ -212,15 +308,36 @@ impl RsyncPage {
             }
         ));
 
+        // Rsync process output signal
+        rsync_process.connect_closure("output", false, closure_local!(
+            #[weak] imp,
+            move |_: RsyncProcess, message: RsyncMessage| {
+                imp.output_window.borrow().append_output(&message);
+            }
+        ));
+
+        // Rsync process file signal
+        rsync_process.connect_closure("file", false, closure_local!(
+            #[weak] imp,
+            move |_: RsyncProcess, file: String| {
+                imp.file_label.set_label(&file);
+                imp.file_box.set_visible(true);
+            }
+        ));
+
         // Rsync process progress signal
         rsync_process.connect_closure("progress", false, closure_local!(
-            #[weak] imp,
-            move |_: RsyncProcess, size: String, speed: String, progress: f64| {
-                imp.transferred_label.set_label(&format!("{size}B"));
-                imp.speed_label.set_label(&speed);
+            #[weak(rename_to = page)] self,
+            move |_: RsyncProcess, progress: RsyncProgress| {
+                page.update_progress(&progress);
+            }
+        ));
 
-                imp.progress_label.set_label(&format!("{progress}%"));
-                imp.progress_bar.set_fraction(progress/100.0);
+        // Rsync process retry signal
+        rsync_process.connect_closure("retry", false, closure_local!(
+            #[weak(rename_to = page)] self,
+            move |_: RsyncProcess, attempt: u32, max_attempts: u32, delay: u32| {
+                page.start_retry_countdown(attempt, max_attempts, delay);
             }
         ));
 
@@ -228,6 +345,8 @@ impl RsyncPage {
         rsync_process.connect_closure("exit", false, closure_local!(
             #[weak(rename_to = page)] self,
             move |_: RsyncProcess, code: i32, messages: RsyncMessages| {
+                page.stop_retry_countdown();
+
                 page.set_exit_status(code, messages);
             }
         ));
@@ -254,25 +373,171 @@ impl RsyncPage {
             }
         ));
 
-        // Output button clicked signal
-        imp.output_button.connect_clicked(clone!(
+        // Retry button clicked signal
+        imp.retry_button.connect_clicked(clone!(
             #[weak(rename_to = page)] self,
             move|_| {
-                let parent = page.root()
-                    .and_downcast::<gtk::Window>()
-                    .expect("Could not downcast to 'GtkWindow'");
+                page.rsync_process().cancel_retry();
+            }
+        ));
+
+        // Output button clicked signals
+        for button in [&imp.output_button, &imp.live_output_button] {
+            button.connect_clicked(clone!(
+                #[weak(rename_to = page)] self,
+                move|_| {
+                    let parent = page.root()
+                        .and_downcast::<gtk::Window>()
+                        .expect("Could not downcast to 'GtkWindow'");
+
+                    page.imp().output_window.borrow().display(&parent);
+                }
+            ));
+        }
+    }
+
+    //---------------------------------------
+    // Setup widgets
+    //---------------------------------------
+    fn setup_widgets(&self) {
+        let imp = self.imp();
 
-                page.imp().output_window.borrow().display(&parent);
+        // Set speed graph draw function
+        imp.speed_graph.set_draw_func(clone!(
+            #[weak] imp,
+            move |area, cr, width, height| {
+                let samples = imp.speed_samples.borrow();
+
+                let peak = samples.iter().copied().fold(0.0, f64::max);
+
+                if samples.len() < 2 || peak <= 0.0 {
+                    return;
+                }
+
+                let (width, height) = (f64::from(width), f64::from(height));
+
+                let step = width / (MAX_SPEED_SAMPLES - 1) as f64;
+
+                // Draw speed line (scaled to peak speed)
+                for (i, speed) in samples.iter().enumerate() {
+                    cr.line_to(i as f64 * step, height - (speed / peak) * (height - 2.0));
+                }
+
+                let color = area.color();
+
+                cr.set_source_color(&color);
+                cr.set_line_width(1.5);
+                let _ = cr.stroke_preserve();
+
+                // Fill area below speed line
+                cr.line_to((samples.len() - 1) as f64 * step, height);
+                cr.line_to(0.0, height);
+                cr.close_path();
+
+                cr.set_source_color(&color.with_alpha(0.2));
+                let _ = cr.fill();
             }
         ));
     }
 
+    //---------------------------------------
+    // Update progress function
+    //---------------------------------------
+    fn update_progress(&self, progress: &RsyncProgress) {
+        let imp = self.imp();
+
+        // Sizes are exact (rsync runs without --human-readable)
+        imp.transferred_label.set_label(&format!("{}B", convert::num_to_size(convert::size_to_num(&progress.size))));
+        imp.speed_label.set_label(&progress.speed);
+
+        imp.progress_label.set_label(&format!("{}%", progress.progress));
+        imp.progress_bar.set_fraction(progress.progress/100.0);
+
+        if !progress.eta.is_empty() {
+            imp.eta_label.set_label(&progress.eta);
+        }
+
+        // Show files checked (total not final during incremental recursion)
+        if progress.files_total > 0 {
+            imp.files_label.set_label(&format!("{} of {}{} files, {} transferred",
+                progress.files_checked,
+                progress.files_total,
+                if progress.incremental { "+" } else { "" },
+                progress.files_transferred
+            ));
+        }
+
+        // Add speed sample to graph
+        let speed = convert::size_to_num(&progress.speed);
+
+        let mut samples = imp.speed_samples.borrow_mut();
+
+        samples.push_back(speed);
+
+        if samples.len() > MAX_SPEED_SAMPLES {
+            samples.pop_front();
+        }
+
+        imp.speed_peak.set(imp.speed_peak.get().max(speed));
+        imp.speed_sum.set(imp.speed_sum.get() + speed);
+        imp.speed_count.set(imp.speed_count.get() + 1);
+
+        imp.speed_graph.queue_draw();
+    }
+
+    //---------------------------------------
+    // Start retry countdown function
+    //---------------------------------------
+    fn start_retry_countdown(&self, attempt: u32, max_attempts: u32, delay: u32) {
+        let imp = self.imp();
+
+        self.stop_retry_countdown();
+
+        imp.message_box.set_css_classes(&["warning", "heading"]);
+        imp.message_image.set_icon_name(Some("dialog-warning-symbolic"));
+
+        imp.button_stack.set_visible_child_name("retry");
+
+        // Update countdown every second
+        let remaining = Cell::new(delay);
+
+        let update = clone!(
+            #[weak] imp,
+            move || {
+                imp.message_label.set_label(&format!("Attempt {attempt} of {max_attempts} in {}s", remaining.get()));
+
+                remaining.set(remaining.get().saturating_sub(1));
+            }
+        );
+
+        update();
+
+        let source = glib::timeout_add_seconds_local(1, move || {
+            update();
+
+            glib::ControlFlow::Continue
+        });
+
+        imp.retry_source.replace(Some(source));
+    }
+
+    //---------------------------------------
+    // Stop retry countdown function
+    //---------------------------------------
+    fn stop_retry_countdown(&self) {
+        if let Some(source) = self.imp().retry_source.take() {
+            source.remove();
+        }
+    }
+
     //---------------------------------------
     // Reset function
     //---------------------------------------
     fn reset(&self) {
         let imp = self.imp();
 
+        self.stop_retry_countdown();
+
         self.set_can_pop(false);
 
         imp.progress_label.set_label("0%");
@@ -281,24 +546,81 @@ impl RsyncPage {
         imp.transferred_label.set_label("0B");
         imp.speed_label.set_label("0B/s");
 
+        imp.files_label.set_label("0 files");
+        imp.eta_label.set_label("-:--:--");
+
+        imp.file_label.set_label("");
+        imp.file_box.set_visible(false);
+
+        imp.speed_samples.borrow_mut().clear();
+        imp.speed_peak.set(0.0);
+        imp.speed_sum.set(0.0);
+        imp.speed_count.set(0);
+        imp.speed_graph.queue_draw();
+
+        imp.speed_summary_label.set_label("");
+        imp.speed_summary_label.set_visible(false);
+
         imp.message_box.set_css_classes(&[]);
         imp.message_image.set_icon_name(Some("rsync-message-symbolic"));
         imp.message_label.set_label("");
 
+        imp.hint_label.set_label("");
+        imp.hint_label.set_visible(false);
+
         imp.stats_stack.set_visible_child_name("empty");
         imp.button_stack.set_visible_child_name("empty");
 
         imp.output_window.borrow().clear_messages();
     }
 
+    //---------------------------------------
+    // Set hint helper function
+    //---------------------------------------
+    fn set_hint(&self, error: &RsyncError, errors: &[String]) {
+        let imp = self.imp();
+
+        // Show error explanation and suggested fix
+        let mut hint = [error.category.desc(), error.category.fix()].into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<&str>>()
+            .join(". ");
+
+        // Show number of vanished files
+        if error.category == RsyncErrorCategory::Vanished {
+            let count = RsyncError::vanished_files(errors).len();
+
+            if count > 0 {
+                hint = format!("{hint} ({count} file{} affected, see output)", if count == 1 { "" } else { "s" });
+            }
+        }
+
+        imp.hint_label.set_label(&hint);
+        imp.hint_label.set_visible(!hint.is_empty());
+    }
+
+    //---------------------------------------
+    // Set dropped options function
+    //---------------------------------------
+    pub fn set_dropped_options(&self, options: &[String]) {
+        let imp = self.imp();
+
+        imp.dropped_label.set_label(&format!("Skipped options not supported by rsync: {}", options.join(" ")));
+        imp.dropped_label.set_visible(!options.is_empty());
+    }
+
     //---------------------------------------
     // Set exit status function
     //---------------------------------------
     pub fn set_exit_status(&self, code: i32, messages: RsyncMessages) {
         let imp = self.imp();
 
+        // Get exit status from profile exit code policy
+        let status = self.profile()
+            .map_or_else(|| RsyncExitStatus::from_code(code, "", ""), |profile| profile.exit_status(code));
+
         // Ensure progress bar at 100% if success
-        if code == 0 {
+        if status == RsyncExitStatus::Success {
             imp.progress_label.set_label("100%");
             imp.progress_bar.set_fraction(1.0);
         }
@@ -306,38 +628,93 @@ impl RsyncPage {
         // Show exit status in message label
         let stats = RsyncProcess::stats(&messages.stats);
 
-        match (code, &stats) {
-            (0, Some(stats)) => {
+        match (status, &stats) {
+            // Stall watchdog terminated rsync
+            _ if messages.stalled => {
+                let timeout = self.profile()
+                    .map(|profile| profile.stall_timeout())
+                    .unwrap_or_default();
+
+                imp.message_box.set_css_classes(&["error", "heading"]);
+                imp.message_image.set_icon_name(Some("rsync-error-symbolic"));
+
+                imp.message_label.set_label(&format!("Transfer stalled: no progress for {timeout}s (code {code})"));
+
+                imp.hint_label.set_label("The source or destination may be unresponsive. Rsync was terminated");
+                imp.hint_label.set_visible(true);
+            }
+
+            (RsyncExitStatus::Success, Some(stats)) => {
                 imp.message_box.set_css_classes(&["success", "heading"]);
                 imp.message_image.set_icon_name(Some("rsync-success-symbolic"));
 
                 imp.message_label.set_label(&format!(
                     "Success: {}B of {}B transferred",
-                    stats.bytes_transferred,
-                    stats.bytes_source
+                    convert::num_to_size(convert::size_to_num(&stats.bytes_transferred)),
+                    convert::num_to_size(convert::size_to_num(&stats.bytes_source))
                 ));
             }
 
-            (0, None) => {
+            (RsyncExitStatus::Success, None) => {
                 imp.message_box.set_css_classes(&["warning", "heading"]);
                 imp.message_image.set_icon_name(Some("rsync-success-symbolic"));
 
                 imp.message_label.set_label("Success: could not retrieve stats");
             }
 
-            (code, _) => {
+            (RsyncExitStatus::Warning, _) => {
+                imp.message_box.set_css_classes(&["warning", "heading"]);
+                imp.message_image.set_icon_name(Some("dialog-warning-symbolic"));
+
+                let error = RsyncError::classify(code, &messages.errors);
+
+                imp.message_label.set_label(&format!("Warning: {} (code {code})", error.message));
+
+                self.set_hint(&error, &messages.errors);
+            }
+
+            (RsyncExitStatus::Error, _) => {
                 imp.message_box.set_css_classes(&["error", "heading"]);
                 imp.message_image.set_icon_name(Some("rsync-error-symbolic"));
 
-                let error = RsyncProcess::error(code, &messages.errors);
+                let error = RsyncError::classify(code, &messages.errors);
 
-                imp.message_label.set_label(&format!("{error} (code {code})"));
+                imp.message_label.set_label(&format!("{} (code {code})", error.message));
+
+                self.set_hint(&error, &messages.errors);
             }
         }
 
+        // Hide current file
+        imp.file_label.set_label("");
+        imp.file_box.set_visible(false);
+
+        if status == RsyncExitStatus::Success {
+            imp.eta_label.set_label("0:00:00");
+        }
+
+        // Show peak and average speed
+        let count = imp.speed_count.get();
+
+        if count > 0 {
+            imp.speed_summary_label.set_label(&format!("Peak speed {}B/s, average speed {}B/s",
+                convert::num_to_size(imp.speed_peak.get()),
+                convert::num_to_size(imp.speed_sum.get() / f64::from(count))
+            ));
+            imp.speed_summary_label.set_visible(true);
+        }
+
+        // Show number of attempts if retried
+        if !messages.attempts.is_empty() {
+            imp.message_label.set_label(&format!("{} after {} attempts",
+                imp.message_label.label(),
+                messages.attempts.len() + 1
+            ));
+        }
+
         // Show stats
         if let Some(stats) = stats {
-            imp.speed_label.set_label(&format!("{}B/s", stats.speed));
+            imp.speed_label.set_label(&format!("{}B/s", convert::num_to_size(convert::size_to_num(&stats.speed))));
 
             imp.stats_table.fill(&stats);
 
@@ -347,20 +724,24 @@ impl RsyncPage {
         }
 
         // Show details
-        if messages.messages.is_empty() && messages.stats.is_empty() && messages.errors.is_empty() {
+        if messages.messages.is_empty() && messages.stats.is_empty() && messages.errors.is_empty()
+            && messages.attempts.is_empty() {
             imp.button_stack.set_visible_child_name("empty");
         } else {
             imp.button_stack.set_visible_child_name("output");
-
-            // Populate output window
-            glib::idle_add_local_once(clone!(
-                #[weak] imp,
-                move || {
-                    imp.output_window.borrow().load_messages(&messages);
-                }
-            ));
         }
 
+        // Set output window summary for log export
+        imp.output_window.borrow().set_summary(&self.title(), &imp.message_label.label());
+
+        // Populate output window (also ends streaming)
+        glib::idle_add_local_once(clone!(
+            #[weak] imp,
+            move || {
+                imp.output_window.borrow().load_messages(&messages);
+            }
+        ));
+
         self.set_can_pop(true);
     }
 }
@@ -1,12 +1,15 @@
-use std::cell::Cell;
-use std::sync::{OnceLock, LazyLock};
+use std::cell::{Cell, RefCell};
+use std::sync::{Arc, OnceLock, LazyLock};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::io;
 use std::process::Stdio;
 use std::str::FromStr;
+use std::time::Instant;
 
 use gtk::subclass::prelude::*;
 use gtk::prelude::{ObjectExt, StaticType};
 use gtk::glib;
+use glib::clone;
 use glib::subclass::Signal;
 
 use strum::EnumString;
@@ -18,18 +21,27 @@ use tokio::{
 };
 use nix::{
     errno::Errno as NixErrno,
-    sys::signal::{kill as nix_kill, Signal as NixSignal},
+    sys::signal::{killpg as nix_killpg, Signal as NixSignal},
     unistd::Pid as NixPid
 };
 use regex::Regex;
 
-use crate::utils::{convert, case};
+use crate::{
+    line_framer::{LineFramer, Frame},
+    utils::{convert, case}
+};
 
 //------------------------------------------------------------------------------
 // CONST Variables
 //------------------------------------------------------------------------------
 const BUFFER_SIZE: usize = 16384;
 pub const ITEMIZE_TAG: &str = "[ITEMIZE]";
+pub const OUT_FORMAT: &str = "%i|%l|%b|%M|%U/%G|%n%L";
+pub const RSYNC_PROGRAM: &str = "rsync";
+pub const RSYNC_PROGRAM_ENV: &str = "SYNCER_RSYNC";
+const OUT_FORMAT_FIELDS: usize = 6;
+const MAX_RETRY_DELAY: u32 = 3600;
+const KILL_GRACE_PERIOD: u32 = 10;
 
 //------------------------------------------------------------------------------
 // ENUM: RsyncSend
@@ -38,9 +50,9 @@ pub const ITEMIZE_TAG: &str = "[ITEMIZE]";
 #[repr(u32)]
 enum RsyncSend {
     Start(Option<i32>),
-    Message(RsyncMsgType, String),
+    Message(RsyncMessage),
     Recurse(String),
-    Progress(String, String, f64),
+    Progress(RsyncProgress),
     Stats(String),
     Error(String),
     Exit(i32)
@@ -66,15 +78,63 @@ pub enum RsyncMsgType {
     None
 }
 
+impl RsyncMsgType {
+    //---------------------------------------
+    // Type name function
+    //---------------------------------------
+    pub fn type_name(self) -> &'static str {
+        match self {
+            Self::Stat => "stat",
+            Self::Error => "error",
+            Self::Info => "info",
+            Self::f => "file",
+            Self::d => "dir",
+            Self::L => "link",
+            Self::D => "device",
+            Self::S => "special",
+            Self::None => "",
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: RsyncMessage
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "RsyncMessage")]
+pub struct RsyncMessage {
+    pub tag: RsyncMsgType,
+    pub changes: String,
+    pub msg: String,
+    pub size: u64,
+    pub transferred: u64,
+    pub mtime: String,
+    pub owner: String,
+    pub raw_path: Vec<u8>
+}
+
+impl RsyncMessage {
+    pub fn new(tag: RsyncMsgType, changes: &str, msg: &str) -> Self {
+        Self {
+            tag,
+            changes: changes.to_owned(),
+            msg: msg.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
 //------------------------------------------------------------------------------
 // STRUCT: RsyncMessages
 //------------------------------------------------------------------------------
 #[derive(Default, Debug, Clone, glib::Boxed)]
 #[boxed_type(name = "RsyncMessages")]
 pub struct RsyncMessages {
-    pub messages: Vec<(RsyncMsgType, String)>,
+    pub messages: Vec<RsyncMessage>,
     pub stats: Vec<String>,
-    pub errors: Vec<String>
+    pub errors: Vec<String>,
+    pub attempts: Vec<(i32, RsyncMessages)>,
+    pub stalled: bool
 }
 
 impl RsyncMessages {
@@ -82,8 +142,8 @@ impl RsyncMessages {
         Self::default()
     }
 
-    pub fn push_message(&mut self, flag: RsyncMsgType, msg: String) {
-        self.messages.push((flag, msg));
+    pub fn push_message(&mut self, message: RsyncMessage) {
+        self.messages.push(message);
     }
 
     pub fn push_stat(&mut self, msg: String) {
@@ -96,9 +156,75 @@ impl RsyncMessages {
 }
 
 //------------------------------------------------------------------------------
-// STRUCT: RsyncStats
+// STRUCT: RsyncProgress
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "RsyncProgress")]
+pub struct RsyncProgress {
+    pub size: String,
+    pub speed: String,
+    pub progress: f64,
+    pub eta: String,
+    pub files_transferred: u32,
+    pub files_checked: u32,
+    pub files_total: u32,
+    pub incremental: bool
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: RsyncRetry
+//------------------------------------------------------------------------------
+#[derive(Debug, Clone)]
+pub struct RsyncRetry {
+    pub max_attempts: u32,
+    pub delay: u32,
+    pub codes: Vec<i32>
+}
+
+impl Default for RsyncRetry {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            delay: 0,
+            codes: vec![]
+        }
+    }
+}
+
+impl RsyncRetry {
+    //---------------------------------------
+    // Backoff delay function
+    //---------------------------------------
+    // Delay doubles after each failed attempt (capped at 1 hour)
+    pub fn backoff_delay(&self, attempt: u32) -> u32 {
+        let factor = 1u32 << attempt.saturating_sub(2).min(10);
+
+        self.delay.saturating_mul(factor).min(MAX_RETRY_DELAY)
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: RsyncWatchdog
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone)]
+pub struct RsyncWatchdog {
+    pub timeout: u32,
+    pub terminate: bool
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: StdoutState
 //------------------------------------------------------------------------------
 #[derive(Default, Debug)]
+struct StdoutState {
+    stats_mode: bool,
+    recurse_mode: bool
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: RsyncStats
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, PartialEq)]
 pub struct RsyncStats {
     pub source_total: String,
     pub source_files: String,
@@ -132,8 +258,20 @@ mod imp {
         running: Cell<bool>,
         #[property(get, set)]
         paused: Cell<bool>,
+        #[property(get, set)]
+        retrying: Cell<bool>,
+        #[property(get, set)]
+        stalled: Cell<bool>,
+        #[property(get, set)]
+        terminating: Cell<bool>,
+        #[property(get, set, default = RSYNC_PROGRAM)]
+        program: RefCell<String>,
 
         pub(super) pid: Cell<Option<NixPid>>,
+        pub(super) retry_cancelled: Cell<bool>,
+        pub(super) last_activity: Cell<Option<Instant>>,
+        pub(super) stall_terminated: Cell<bool>,
+        pub(super) terminate_requested: Arc<AtomicBool>,
     }
 
     //---------------------------------------
@@ -159,11 +297,20 @@ mod imp {
                     Signal::builder("message")
                         .param_types([String::static_type()])
                         .build(),
+                    Signal::builder("output")
+                        .param_types([RsyncMessage::static_type()])
+                        .build(),
+                    Signal::builder("file")
+                        .param_types([String::static_type()])
+                        .build(),
                     Signal::builder("progress")
+                        .param_types([RsyncProgress::static_type()])
+                        .build(),
+                    Signal::builder("retry")
                         .param_types([
-                            String::static_type(),
-                            String::static_type(),
-                            f64::static_type()
+                            u32::static_type(),
+                            u32::static_type(),
+                            u32::static_type()
                         ])
                         .build(),
                     Signal::builder("exit")
@@ -200,6 +347,12 @@ impl RsyncProcess {
     // Handle progress async function
     //---------------------------------------
     async fn handle_progress(line: &str, sender: &Sender::<RsyncSend>) {
+        // rsync < 3.1 (--progress) reports xfer#/to-check
+        static EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"\(xfe?r#(?P<xfr>\d+), (?P<mode>to|ir)-ch(?:ec)?k=(?P<chk>\d+)/(?P<total>\d+)\)")
+                .expect("Failed to compile Regex")
+        });
+
         for chunk in line.trim_start_matches('\r').split_terminator('\r') {
             let parts: Vec<&str> = chunk
                 .split_whitespace()
@@ -210,12 +363,26 @@ impl RsyncProcess {
                 parts[2],
                 parts[1].trim_end_matches('%').parse::<f64>()
             ) {
+                let mut rsync_progress = RsyncProgress {
+                    size: size.into(),
+                    speed: speed.into(),
+                    progress,
+                    eta: parts.get(3).map(|&eta| eta.into()).unwrap_or_default(),
+                    ..Default::default()
+                };
+
+                // Get file counters (xfr#N, to-chk=N/N or ir-chk=N/N)
+                if let Some(caps) = EXPR.captures(chunk) {
+                    let total = convert::str_to_num::<u32>(&caps["total"]);
+
+                    rsync_progress.files_transferred = convert::str_to_num::<u32>(&caps["xfr"]);
+                    rsync_progress.files_checked = total.saturating_sub(convert::str_to_num::<u32>(&caps["chk"]));
+                    rsync_progress.files_total = total;
+                    rsync_progress.incremental = &caps["mode"] == "ir";
+                }
+
                 sender
-                    .send(RsyncSend::Progress(
-                        size.into(),
-                        speed.into(),
-                        progress
-                    ))
+                    .send(RsyncSend::Progress(rsync_progress))
                     .await
                     .expect("Could not send through channel");
             }
@@ -223,179 +390,314 @@ impl RsyncProcess {
     }
 
     //---------------------------------------
-    // Handle message async function
+    // Unescape name helper function
     //---------------------------------------
-    async fn handle_message(line: &str, sender: &Sender::<RsyncSend>) {
-        let (tag, msg) = if line.starts_with(ITEMIZE_TAG) && let Some((changes, msg)) = line
-            .trim_start_matches(ITEMIZE_TAG)
-            .split_once(' ') {
-                if changes.starts_with('*') {
-                    (
-                        RsyncMsgType::Info,
-                        format!("{} {}",
-                            case::capitalize_first(changes.trim_start_matches('*')),
-                            msg
-                        )
-                    )
-                } else {
-                    (
-                        changes.get(1..2)
-                            .and_then(|c| RsyncMsgType::from_str(c).ok())
-                            .unwrap_or_default(),
-                        msg.to_owned()
-                    )
+    // Decodes rsync's "\#ooo" escapes of non-printable bytes in file names
+    fn unescape_name(bytes: &[u8]) -> Vec<u8> {
+        let mut name = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if bytes[i] == b'\\' && bytes.get(i + 1) == Some(&b'#')
+                && let Some(digits) = bytes.get(i + 2..i + 5)
+                && digits.iter().all(|b| (b'0'..=b'7').contains(b))
+                && let Ok(byte) = u8::try_from(digits.iter().fold(0u32, |n, b| n * 8 + u32::from(b - b'0'))) {
+                    name.push(byte);
+                    i += 5;
+
+                    continue;
                 }
+
+            name.push(bytes[i]);
+            i += 1;
+        }
+
+        name
+    }
+
+    //---------------------------------------
+    // Handle message async function
+    //---------------------------------------
+    async fn handle_message(line: &[u8], sender: &Sender::<RsyncSend>) {
+        let fields: Vec<&[u8]> = line.strip_prefix(ITEMIZE_TAG.as_bytes())
+            .map(|line| line.splitn(OUT_FORMAT_FIELDS, |&b| b == b'|').collect())
+            .unwrap_or_default();
+
+        // Itemized line (changes|length|bytes|mtime|user/group|name)
+        let message = if let [changes, size, transferred, mtime, owner, name] = fields[..] {
+            let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+
+            let changes = text(changes).trim_end().to_owned();
+
+            let tag = if changes.starts_with('*') {
+                RsyncMsgType::Info
+            } else {
+                changes.get(1..2)
+                    .and_then(|c| RsyncMsgType::from_str(c).ok())
+                    .unwrap_or_default()
+            };
+
+            // Keep raw bytes of path (without link target) for file operations
+            let name = Self::unescape_name(name);
+
+            let link_sep: &[u8] = if changes.starts_with('h') { b" => " } else { b" -> " };
+
+            let raw_path = if tag == RsyncMsgType::L || changes.starts_with('h') {
+                name.windows(link_sep.len())
+                    .position(|w| w == link_sep)
+                    .map_or(&name[..], |i| &name[..i])
+                    .to_vec()
+            } else {
+                name.clone()
+            };
+
+            let msg = if changes.starts_with('*') {
+                format!("{} {}", case::capitalize_first(changes.trim_start_matches('*')), text(&name))
             } else {
-                (RsyncMsgType::Info, case::capitalize_first(line))
+                text(&name)
             };
 
-        sender.send(RsyncSend::Message(tag, msg))
+            RsyncMessage {
+                tag,
+                changes,
+                msg,
+                raw_path,
+                size: convert::size_to_num(&text(size)) as u64,
+                transferred: convert::size_to_num(&text(transferred)) as u64,
+                mtime: text(mtime),
+                owner: text(owner)
+            }
+        } else {
+            RsyncMessage::new(RsyncMsgType::Info, "", &case::capitalize_first(&String::from_utf8_lossy(line)))
+        };
+
+        sender.send(RsyncSend::Message(message))
             .await
             .expect("Could not send through channel");
     }
 
+    //---------------------------------------
+    // Handle frame async function
+    //---------------------------------------
+    async fn handle_frame(frame: &Frame, state: &mut StdoutState, sender: &Sender::<RsyncSend>) {
+        let text = frame.text();
+        let line = text.as_ref();
+
+        // Status line (overwrites previous line)
+        if frame.update {
+            if line.ends_with("to consider") {
+                // Recursion end line
+                state.recurse_mode = false;
+
+                sender.send(RsyncSend::Message(RsyncMessage::new(RsyncMsgType::Info, "", line.trim_start())))
+                    .await
+                    .expect("Could not send through channel");
+            } else if state.recurse_mode && line.ends_with("files...") {
+                // Recursion line
+                sender.send(RsyncSend::Recurse(line.into()))
+                    .await
+                    .expect("Could not send through channel");
+            } else {
+                // Progress line
+                Self::handle_progress(line, sender).await;
+            }
+
+            return;
+        }
+
+        // Stats line
+        if state.stats_mode || line.starts_with("Number of files:") {
+            state.stats_mode = true;
+
+            sender.send(RsyncSend::Stats(line.into()))
+                .await
+                .expect("Could not send through channel");
+
+            return;
+        }
+
+        // Recursion start line
+        if line.starts_with("building file list ...") {
+            state.recurse_mode = true;
+
+            sender.send(RsyncSend::Message(RsyncMessage::new(RsyncMsgType::Info, "", line)))
+                .await
+                .expect("Could not send through channel");
+
+            return;
+        }
+
+        // Recursion line
+        if state.recurse_mode && line.starts_with(' ') && line.ends_with("files...") {
+            sender.send(RsyncSend::Recurse(line.into()))
+                .await
+                .expect("Could not send through channel");
+
+            return;
+        }
+
+        // Recursion end line
+        if line.ends_with("to consider") {
+            state.recurse_mode = false;
+
+            sender.send(RsyncSend::Message(RsyncMessage::new(RsyncMsgType::Info, "", line)))
+                .await
+                .expect("Could not send through channel");
+
+            return;
+        }
+
+        // Message line
+        Self::handle_message(&frame.bytes, sender).await;
+    }
+
     //---------------------------------------
     // Parse stdout async function
     //---------------------------------------
     async fn parse_stdout(mut stdout: ChildStdout, sender: Sender::<RsyncSend>) {
         let mut buffer = [0u8; BUFFER_SIZE];
-        let mut pending = vec![];
+        let mut framer = LineFramer::new();
 
-        let mut stats_mode = false;
-        let mut recurse_mode = false;
+        let mut state = StdoutState::default();
 
-        while let Ok(read) = stdout.read(&mut buffer).await {
-            // Break if stdout is empty
-            if read == 0 {
-                break;
+        // Process stdout line by line (lines may span reads)
+        while let Ok(read) = stdout.read(&mut buffer).await && read > 0 {
+            for frame in framer.push(&buffer[..read]) {
+                Self::handle_frame(&frame, &mut state, &sender).await;
             }
+        }
 
-            // Add buffer to pending
-            pending.extend_from_slice(&buffer[..read]);
+        if let Some(frame) = framer.finish() {
+            Self::handle_frame(&frame, &mut state, &sender).await;
+        }
+    }
 
-            // Continue if buffer is full
-            if read == BUFFER_SIZE {
-                continue;
+    //---------------------------------------
+    // Parse stderr async function
+    //---------------------------------------
+    async fn parse_stderr(mut stderr: ChildStderr, sender: Sender::<RsyncSend>) {
+        let mut buffer = [0u8; BUFFER_SIZE];
+        let mut framer = LineFramer::new();
+
+        // Process stderr line by line (lines may span reads)
+        while let Ok(read) = stderr.read(&mut buffer).await && read > 0 {
+            for frame in framer.push(&buffer[..read]) {
+                sender.send(RsyncSend::Error(case::capitalize_first(&frame.text())))
+                    .await
+                    .expect("Could not send through channel");
             }
+        }
 
-            // Drain pending and convert to string
-            let bytes = std::mem::take(&mut pending);
-            let text = String::from_utf8_lossy(&bytes);
+        if let Some(frame) = framer.finish() {
+            sender.send(RsyncSend::Error(case::capitalize_first(&frame.text())))
+                .await
+                .expect("Could not send through channel");
+        }
+    }
 
-            // Process stdout line by line
-            for line in text.lines().filter(|&line| !line.is_empty()) {
-                // Progress line
-                if line.starts_with('\r') {
-                    Self::handle_progress(line, &sender).await;
+    //---------------------------------------
+    // Start function
+    //---------------------------------------
+    pub async fn start(&self, mut args: Vec<String>, retry: RsyncRetry, watchdog: RsyncWatchdog) -> io::Result<()> {
+        let imp = self.imp();
 
-                    continue;
-                }
+        imp.retry_cancelled.set(false);
 
-                // Recursion line
-                if recurse_mode && line.starts_with(' ')
-                    && (line.ends_with("files...") || line.ends_with("files...\r")) {
-                        for chunk in line.split_terminator('\r') {
-                            sender.send(RsyncSend::Recurse(chunk.into()))
-                                .await
-                                .expect("Could not send through channel");
-                        }
+        // Keep partially transferred files if retrying
+        if retry.max_attempts > 1 && !args.iter().any(|arg| arg == "--partial") {
+            args.insert(0, "--partial".to_owned());
+        }
 
-                    continue;
-                }
+        let mut attempts: Vec<(i32, RsyncMessages)> = vec![];
 
-                // Stats line
-                if stats_mode || line.starts_with("Number of files:") {
-                    stats_mode = true;
-                    sender.send(RsyncSend::Stats(line.into()))
-                        .await
-                        .expect("Could not send through channel");
+        loop {
+            let (code, mut messages) = match self.run(args.clone(), &watchdog).await {
+                Ok(result) => result,
+                Err(error) => {
+                    self.set_running(false);
+                    self.set_paused(false);
 
-                    continue;
+                    return Err(error);
                 }
+            };
 
-                // Recursion start line
-                if line.starts_with("building file list ...") {
-                    recurse_mode = true;
-
-                    for chunk in line.split_terminator('\r') {
-                        if chunk.starts_with("building file list ...") {
-                            sender.send(RsyncSend::Message(RsyncMsgType::Info, chunk.into()))
-                                .await
-                                .expect("Could not send through channel");
-                        } else {
-                            sender.send(RsyncSend::Recurse(chunk.into()))
-                                .await
-                                .expect("Could not send through channel");
-                        }
-                    }
+            let attempt = attempts.len() as u32 + 1;
+
+            // Retry if exit code is retryable
+            if attempt < retry.max_attempts && retry.codes.contains(&code)
+                && self.wait_retry(attempt + 1, &retry).await {
+                    attempts.push((code, messages));
 
                     continue;
                 }
 
-                // Recursion end line
-                if line.ends_with("to consider") {
-                    recurse_mode = false;
-
-                    for chunk in line.split('\r') {
-                        if chunk.ends_with("to consider") {
-                            sender.send(RsyncSend::Message(RsyncMsgType::Info, chunk.into()))
-                                .await
-                                .expect("Could not send through channel");
-                        } else {
-                            sender.send(RsyncSend::Recurse(chunk.into()))
-                                .await
-                                .expect("Could not send through channel");
-                        }
-                    }
+            messages.attempts = attempts;
 
-                    continue;
-                }
+            self.set_running(false);
+            self.set_paused(false);
 
-                // Message line
-                Self::handle_message(line, &sender).await;
-            }
+            self.emit_by_name::<()>("exit", &[&code, &messages]);
+
+            return Ok(());
         }
     }
 
     //---------------------------------------
-    // Parse stderr async function
+    // Wait retry async function
     //---------------------------------------
-    async fn parse_stderr(mut stderr: ChildStderr, sender: Sender::<RsyncSend>) {
-        let mut buffer = [0u8; BUFFER_SIZE];
+    async fn wait_retry(&self, attempt: u32, retry: &RsyncRetry) -> bool {
+        let imp = self.imp();
 
-        while let Ok(read) = stderr.read(&mut buffer).await {
-            // Break if stderr is empty
-            if read == 0 {
-                break;
-            }
+        if imp.retry_cancelled.get() {
+            return false;
+        }
 
-            // Read stderr and process line by line
-            let error = String::from_utf8_lossy(&buffer[..read]);
+        let delay = retry.backoff_delay(attempt);
 
-            for line in error.lines().filter(|&line| !line.is_empty()) {
-                sender.send(RsyncSend::Error(case::capitalize_first(line)))
-                    .await
-                    .expect("Could not send through channel");
+        self.set_retrying(true);
+
+        self.emit_by_name::<()>("retry", &[&attempt, &retry.max_attempts, &delay]);
+
+        for _ in 0..delay {
+            glib::timeout_future_seconds(1).await;
+
+            if imp.retry_cancelled.get() {
+                break;
             }
         }
+
+        self.set_retrying(false);
+
+        !imp.retry_cancelled.get()
     }
 
     //---------------------------------------
-    // Start function
+    // Run async function
     //---------------------------------------
-    pub async fn start(&self, args: Vec<String>) -> io::Result<()> {
+    async fn run(&self, args: Vec<String>, watchdog: &RsyncWatchdog) -> io::Result<(i32, RsyncMessages)> {
         // Spawn tokio task to run rsync
         let (sender, receiver) = async_channel::bounded(1);
 
+        let program = self.program();
+
+        // Terminate escalation owns cleanup of the process group
+        let terminate_requested = self.imp().terminate_requested.clone();
+
+        terminate_requested.store(false, Ordering::Relaxed);
+
         let rsync_task = Self::runtime().spawn(
             async move {
-                // Start rsync
-                let mut rsync_process = Command::new("rsync")
+                // Start rsync in its own process group
+                let mut rsync_process = Command::new(program)
                     .args(args)
+                    .env("LC_ALL", "C")
                     .stdout(Stdio::piped())
                     .stderr(Stdio::piped())
+                    .process_group(0)
                     .spawn()?;
 
+                let pgid = rsync_process.id().map(|id| NixPid::from_raw(id as i32));
+
                 // Get sdtout/stderr handles
                 let stdout = rsync_process.stdout.take()
                     .ok_or_else(|| io::Error::other("Could not get stdout"))?;
@@ -419,12 +721,19 @@ impl RsyncProcess {
 
                 let stderr_task = tokio::spawn(Self::parse_stderr(stderr, sender_err));
 
-                // Wait for stdout, stderr and process
-                let (_, _, status_res) = tokio::join!(
-                    stdout_task,
-                    stderr_task,
-                    rsync_process.wait()
-                );
+                // Wait for process
+                let status_res = rsync_process.wait().await;
+
+                // Kill processes left in group after rsync exited on its own (orphans keep
+                // stdout/stderr open), the group id cannot be reused while it has members
+                if let Some(pgid) = pgid
+                    && !terminate_requested.load(Ordering::Relaxed)
+                    && nix_killpg(pgid, None).is_ok() {
+                        let _ = nix_killpg(pgid, NixSignal::SIGKILL);
+                    }
+
+                // Wait for stdout and stderr
+                let _ = tokio::join!(stdout_task, stderr_task);
 
                 let code = status_res
                     .map_or_else(|_| None, |status| status.code());
@@ -443,28 +752,48 @@ impl RsyncProcess {
         let imp = self.imp();
 
         let mut messages = RsyncMessages::new();
+        let mut exit_code = 1;
+
+        imp.stall_terminated.set(false);
+
+        // Start stall watchdog
+        let watchdog_source = (watchdog.timeout > 0)
+            .then(|| self.start_watchdog(watchdog));
 
         while let Ok(msg) = receiver.recv().await {
             match msg {
                 RsyncSend::Start(id) => {
                     imp.pid.set(id.map(NixPid::from_raw));
                     self.set_running(true);
+                    self.set_terminating(false);
 
                     self.emit_by_name::<()>("start", &[]);
                 }
 
-                RsyncSend::Message(flag, msg) => {
-                    self.emit_by_name::<()>("message", &[&msg]);
+                RsyncSend::Message(message) => {
+                    self.activity();
 
-                    messages.push_message(flag, msg);
+                    self.emit_by_name::<()>("message", &[&message.msg]);
+
+                    if message.tag == RsyncMsgType::f {
+                        self.emit_by_name::<()>("file", &[&message.msg]);
+                    }
+
+                    self.emit_by_name::<()>("output", &[&message]);
+
+                    messages.push_message(message);
                 }
 
                 RsyncSend::Recurse(message) => {
+                    self.activity();
+
                     self.emit_by_name::<()>("message", &[&message]);
                 }
 
-                RsyncSend::Progress(size, speed, progress) => {
-                    self.emit_by_name::<()>("progress", &[&size, &speed, &progress]);
+                RsyncSend::Progress(progress) => {
+                    self.activity();
+
+                    self.emit_by_name::<()>("progress", &[&progress]);
                 }
 
                 RsyncSend::Stats(stat) => {
@@ -472,43 +801,156 @@ impl RsyncProcess {
                 }
 
                 RsyncSend::Error(error) => {
+                    self.emit_by_name::<()>("output", &[&RsyncMessage::new(RsyncMsgType::Error, "", &error)]);
+
                     messages.push_error(error);
                 }
 
                 RsyncSend::Exit(code) => {
-                    self.set_running(false);
-                    self.set_paused(false);
                     imp.pid.set(None);
+                    self.set_terminating(false);
 
-                    self.emit_by_name::<()>("exit", &[&code, &messages]);
+                    exit_code = code;
                 }
             }
         }
 
-        rsync_task.await?
+        // Stop stall watchdog
+        if let Some(source) = watchdog_source {
+            source.remove();
+        }
+
+        self.set_stalled(false);
+
+        // Keep stall as exit reason if watchdog terminated rsync
+        messages.stalled = imp.stall_terminated.take();
+
+        rsync_task.await??;
+
+        Ok((exit_code, messages))
+    }
+
+    //---------------------------------------
+    // Activity helper function
+    //---------------------------------------
+    fn activity(&self) {
+        self.imp().last_activity.set(Some(Instant::now()));
+
+        if self.stalled() {
+            self.set_stalled(false);
+        }
+    }
+
+    //---------------------------------------
+    // Start watchdog helper function
+    //---------------------------------------
+    fn start_watchdog(&self, watchdog: &RsyncWatchdog) -> glib::SourceId {
+        self.activity();
+
+        let timeout = u64::from(watchdog.timeout);
+        let terminate = watchdog.terminate;
+
+        glib::timeout_add_seconds_local(1, clone!(
+            #[weak(rename_to = process)] self,
+            #[upgrade_or] glib::ControlFlow::Break,
+            move || {
+                let imp = process.imp();
+
+                // Paused transfers are not stalled
+                if process.paused() {
+                    imp.last_activity.set(Some(Instant::now()));
+
+                    return glib::ControlFlow::Continue;
+                }
+
+                let elapsed = imp.last_activity.get()
+                    .map_or(0, |instant| instant.elapsed().as_secs());
+
+                if elapsed >= timeout && !process.stalled() {
+                    process.set_stalled(true);
+
+                    if terminate {
+                        imp.stall_terminated.set(true);
+
+                        let _ = process.terminate_group();
+                    }
+                }
+
+                glib::ControlFlow::Continue
+            }
+        ))
     }
 
     //---------------------------------------
     // Terminate function
     //---------------------------------------
+    // Terminates rsync gracefully, or kills it if already terminating
     pub fn terminate(&self) -> Result<(), NixErrno> {
         let imp = self.imp();
 
-        if let Some(pid) = imp.pid.get() {
-            // Resume rsync if paused
-            if self.paused() {
-                nix_kill(pid, NixSignal::SIGCONT)?;
+        // Do not retry after terminating
+        imp.retry_cancelled.set(true);
+
+        if self.terminating() {
+            self.kill()
+        } else {
+            self.terminate_group()
+        }
+    }
+
+    //---------------------------------------
+    // Terminate group helper function
+    //---------------------------------------
+    // Sends SIGTERM to the process group, then SIGKILL after a grace period
+    fn terminate_group(&self) -> Result<(), NixErrno> {
+        let Some(pid) = self.imp().pid.get() else {
+            return Ok(());
+        };
+
+        // Resume rsync if paused
+        if self.paused() {
+            nix_killpg(pid, NixSignal::SIGCONT)?;
+
+            self.set_paused(false);
+        }
+
+        // Terminate rsync
+        self.imp().terminate_requested.store(true, Ordering::Relaxed);
+
+        nix_killpg(pid, NixSignal::SIGTERM)?;
 
-                self.set_paused(false);
+        self.set_terminating(true);
+
+        glib::timeout_add_seconds_local_once(KILL_GRACE_PERIOD, clone!(
+            #[weak(rename_to = process)] self,
+            move || {
+                if process.imp().pid.get() == Some(pid) {
+                    let _ = process.kill();
+                }
             }
+        ));
+
+        Ok(())
+    }
 
-            // Terminate rsync
-            nix_kill(pid, NixSignal::SIGTERM)?;
+    //---------------------------------------
+    // Kill function
+    //---------------------------------------
+    pub fn kill(&self) -> Result<(), NixErrno> {
+        if let Some(pid) = self.imp().pid.get() {
+            nix_killpg(pid, NixSignal::SIGKILL)?;
         }
 
         Ok(())
     }
 
+    //---------------------------------------
+    // Cancel retry function
+    //---------------------------------------
+    pub fn cancel_retry(&self) {
+        self.imp().retry_cancelled.set(true);
+    }
+
     //---------------------------------------
     // Pause function
     //---------------------------------------
@@ -517,7 +959,7 @@ impl RsyncProcess {
 
         // Pause rsync if not paused
         if !self.paused() && let Some(pid) = imp.pid.get() {
-            nix_kill(pid, NixSignal::SIGSTOP)?;
+            nix_killpg(pid, NixSignal::SIGSTOP)?;
 
             self.set_paused(true);
         }
@@ -533,7 +975,7 @@ impl RsyncProcess {
 
         // Resume rsync if paused
         if self.paused() && let Some(pid) = imp.pid.get() {
-            nix_kill(pid, NixSignal::SIGCONT)?;
+            nix_killpg(pid, NixSignal::SIGCONT)?;
 
             self.set_paused(false);
         }
@@ -545,99 +987,103 @@ impl RsyncProcess {
     // Stats function
     //---------------------------------------
     pub fn stats(stats: &[String]) -> Option<RsyncStats> {
-        static EXPR: LazyLock<Regex> = LazyLock::new(|| {
-            Regex::new(r"(?x)
-                Number\s*of\s*files:\s*(?P<st>[\d,.]+)\s*\(?(?:reg:\s*(?P<sf>[\d,.]+))?,?\s*(?:dir:\s*(?P<sd>[\d,.]+))?,?\s*(?:link:\s*(?P<sl>[\d,.]+))?,?\s*(?:special:\s*(?P<ss>[\d,.]+))?,?\s*\)?\n
-                Number\s*of\s*created\s*files:\s*(?P<dt>[\d,.]+)\s*\(?(?:reg:\s*(?P<df>[\d,.]+))?,?\s*(?:dir:\s*(?P<dd>[\d,.]+))?,?\s*(?:link:\s*(?P<dl>[\d,.]+))?,?\s*(?:special:\s*(?P<ds>[\d,.]+))?,?\s*\)?\n
-                Number\s*of\s*deleted\s*files:\s*(?P<dr>[\d,.]+)\s*\(?(?:reg:\s*(?P<nf>[\d,.]+))?,?\s*(?:dir:\s*(?P<nd>[\d,.]+))?,?\s*(?:link:\s*(?P<nl>[\d,.]+))?,?\s*(?:special:\s*(?P<ns>[\d,.]+))?,?\s*\)?\n
-                Number\s*of\s*regular\s*files\s*transferred:\s*(?P<tt>[\d,.]+)\n
-                Total\s*file\s*size:\s*(?P<bs>.+)\s*bytes\n
-                Total\s*transferred\s*file\s*size:\s*(?P<bt>.+)\s*bytes\n
-                .*\n
-                .*\n
-                .*\n
-                .*\n
-                .*\n
-                .*\n
-                .*\n
-                sent\s*.*?\s*bytes\s*received\s*.*?\s*bytes(?P<ts>.*?)\s*bytes
-            ")
-            .expect("Failed to compile Regex")
+        static COUNT_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?P<kind>reg|dir|link|dev|special):\s*(?P<num>[\d,.]+)")
+                .expect("Failed to compile Regex")
         });
 
-        EXPR.captures(&stats.join("\n"))
-            .map(|caps| {
-                let regex_match = |m: &str| -> String {
-                    caps.name(m)
-                        .map_or("0", |m| m.as_str().trim_end_matches(',').trim())
-                        .to_owned()
-                };
-
-                let d_total = regex_match("dt");
-                let d_files = regex_match("df");
-                let d_transf = regex_match("tt");
-
-                let dest_total = convert::max_str::<u32>(&d_total, &d_transf);
-                let dest_files = convert::max_str::<u32>(&d_files, &d_transf);
-
-                RsyncStats {
-                    source_total: regex_match("st"),
-                    source_files: regex_match("sf"),
-                    source_dirs: regex_match("sd"),
-                    source_links: regex_match("sl"),
-                    source_specials: regex_match("ss"),
-                    destination_total: dest_total,
-                    destination_files: dest_files,
-                    destination_dirs: regex_match("dd"),
-                    destination_links: regex_match("dl"),
-                    destination_specials: regex_match("ds"),
-                    destination_deleted: regex_match("dr"),
-                    bytes_source: regex_match("bs"),
-                    bytes_transferred: regex_match("bt"),
-                    speed: regex_match("ts")
-                }
-            })
-    }
-
-    //---------------------------------------
-    // Error function
-    //---------------------------------------
-    pub fn error(code: i32, errors: &[String]) -> String {
-        static EXPR: LazyLock<Regex> = LazyLock::new(|| {
-            Regex::new(r"^(?P<err>[^(]*).*")
+        static SPEED_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"sent\s+.*?\s+bytes\s+received\s+.*?\s+bytes\s+(?P<speed>.*?)\s*bytes/sec")
                 .expect("Failed to compile Regex")
         });
 
-        // Get first (detailed) and last (main) errors
-        let (err_detail, err_main) = (errors.first(), errors.last());
-
-        // Helper closure to extract error
-        let extract_error = |msg: Option<&String>| -> Option<String> {
-            EXPR.captures(msg?)?
-                .name("err")
-                .map(|m| {
-                    let s = m.as_str().trim()
-                        .trim_end_matches('.')
-                        .replace("Rsync error: ", "")
-                        .replace("Rsync warning: ", "");
-
-                    case::capitalize_first(&s)
-                })
-        };
+        let mut rsync_stats = RsyncStats::default();
+        let mut found = false;
+        let mut transferred = String::new();
+
+        // Match stats lines by key (line order and count differ between versions)
+        for line in stats {
+            if let Some(caps) = SPEED_EXPR.captures(line) {
+                rsync_stats.speed = caps["speed"].trim().to_owned();
+
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+
+            let value = value.trim();
 
-        // Get error string
-        match code {
-            // Terminated by user
-            20 => Some(String::from("Terminated by user")),
+            let number = || -> String {
+                value.split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches(',')
+                    .to_owned()
+            };
 
-            // Usage error
-            1 => extract_error(err_detail)
-                .or_else(|| extract_error(err_main)),
+            let count = |kind: &str| -> String {
+                COUNT_EXPR.captures_iter(value)
+                    .find(|caps| &caps["kind"] == kind)
+                    .map(|caps| caps["num"].trim_end_matches(',').to_owned())
+                    .unwrap_or_default()
+            };
+
+            let size = || -> String {
+                value.trim_end_matches("bytes")
+                    .trim_end_matches(" B")
+                    .trim()
+                    .to_owned()
+            };
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "number of files" => {
+                    found = true;
+
+                    rsync_stats.source_total = number();
+                    rsync_stats.source_files = count("reg");
+                    rsync_stats.source_dirs = count("dir");
+                    rsync_stats.source_links = count("link");
+                    rsync_stats.source_specials = count("special");
+                }
+                "number of created files" => {
+                    rsync_stats.destination_total = number();
+                    rsync_stats.destination_files = count("reg");
+                    rsync_stats.destination_dirs = count("dir");
+                    rsync_stats.destination_links = count("link");
+                    rsync_stats.destination_specials = count("special");
+                }
+                "number of deleted files" => rsync_stats.destination_deleted = number(),
+                "number of regular files transferred" | "number of files transferred" => transferred = number(),
+                "total file size" => rsync_stats.bytes_source = size(),
+                "total transferred file size" => rsync_stats.bytes_transferred = size(),
+                _ => {}
+            }
+        }
+
+        if !found {
+            return None;
+        }
 
-            // Other error
-            _ => extract_error(err_main)
+        // Default missing values to zero
+        for value in [
+            &mut rsync_stats.source_total, &mut rsync_stats.source_files, &mut rsync_stats.source_dirs,
+            &mut rsync_stats.source_links, &mut rsync_stats.source_specials,
+            &mut rsync_stats.destination_total, &mut rsync_stats.destination_files, &mut rsync_stats.destination_dirs,
+            &mut rsync_stats.destination_links, &mut rsync_stats.destination_specials, &mut rsync_stats.destination_deleted,
+            &mut rsync_stats.bytes_source, &mut rsync_stats.bytes_transferred, &mut rsync_stats.speed,
+            &mut transferred
+        ] {
+            if value.is_empty() {
+                *value = String::from("0");
+            }
         }
-        .unwrap_or_else(|| String::from("Unknown error"))
+
+        rsync_stats.destination_total = convert::max_str::<u32>(&rsync_stats.destination_total, &transferred);
+        rsync_stats.destination_files = convert::max_str::<u32>(&rsync_stats.destination_files, &transferred);
+
+        Some(rsync_stats)
     }
 }
 
@@ -649,3 +1095,319 @@ impl Default for RsyncProcess {
         glib::Object::builder().build()
     }
 }
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicUsize;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use glib::closure_local;
+
+    // Wrapper running tests/fake-rsync.bash with a transcript (in a temp dir removed on drop)
+    struct FakeRsync {
+        dir: PathBuf,
+        program: String
+    }
+
+    impl FakeRsync {
+        fn new(transcript: &str) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+            let root = env!("CARGO_MANIFEST_DIR");
+
+            let dir = std::env::temp_dir()
+                .join(format!("syncer-fake-rsync-{}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+
+            // Remove leftovers of an earlier run with the same process id
+            let _ = fs::remove_dir_all(&dir);
+
+            fs::create_dir(&dir)
+                .expect("Could not create fake rsync dir");
+
+            let path = dir.join("rsync");
+
+            fs::write(&path, format!("#!/bin/sh\nFAKE_RSYNC_TRANSCRIPT='{root}/tests/transcripts/{transcript}' exec '{root}/tests/fake-rsync.bash' \"$@\"\n"))
+                .expect("Could not write fake rsync wrapper");
+
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+                .expect("Could not make fake rsync wrapper executable");
+
+            Self {
+                program: path.to_string_lossy().into_owned(),
+                dir
+            }
+        }
+    }
+
+    impl Drop for FakeRsync {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    // Runs future on a new thread default main context
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let context = glib::MainContext::new();
+
+        context.with_thread_default(|| context.block_on(future))
+            .expect("Could not acquire main context")
+    }
+
+    // Checks if any live (non-zombie) process is left in group
+    fn group_alive(pgid: NixPid) -> bool {
+        fs::read_dir("/proc").into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| fs::read_to_string(entry.path().join("stat")).ok())
+            .any(|stat| {
+                // Fields after the command name: state ppid pgrp ...
+                let fields: Vec<&str> = stat.rsplit_once(')')
+                    .map(|(_, rest)| rest.split_whitespace().collect())
+                    .unwrap_or_default();
+
+                fields.get(2).is_some_and(|pgrp| *pgrp == pgid.as_raw().to_string())
+                    && fields.first().is_some_and(|state| *state != "Z")
+            })
+    }
+
+    // Runs fake rsync, recording start/message/progress/exit signals
+    fn run_transcript(transcript: &str) -> (Vec<&'static str>, i32, RsyncMessages) {
+        let fake_rsync = FakeRsync::new(transcript);
+
+        let process = RsyncProcess::default();
+        process.set_program(fake_rsync.program.clone());
+
+        let signals: Rc<RefCell<Vec<&'static str>>> = Rc::default();
+        let exit: Rc<RefCell<Option<(i32, RsyncMessages)>>> = Rc::default();
+
+        process.connect_closure("start", false, closure_local!(
+            #[strong] signals,
+            move |_: RsyncProcess| signals.borrow_mut().push("start")
+        ));
+
+        process.connect_closure("message", false, closure_local!(
+            #[strong] signals,
+            move |_: RsyncProcess, _: String| signals.borrow_mut().push("message")
+        ));
+
+        process.connect_closure("progress", false, closure_local!(
+            #[strong] signals,
+            move |_: RsyncProcess, _: RsyncProgress| signals.borrow_mut().push("progress")
+        ));
+
+        process.connect_closure("exit", false, closure_local!(
+            #[strong] signals,
+            #[strong] exit,
+            move |_: RsyncProcess, code: i32, messages: RsyncMessages| {
+                signals.borrow_mut().push("exit");
+                exit.replace(Some((code, messages)));
+            }
+        ));
+
+        block_on(process.start(vec![], RsyncRetry::default(), RsyncWatchdog::default()))
+            .expect("Could not run fake rsync");
+
+        let (code, messages) = exit.take()
+            .expect("Fake rsync did not exit");
+
+        (signals.take(), code, messages)
+    }
+
+    // Builds expected stats (source total/reg/dir/link/special, destination ... /deleted)
+    fn expected_stats(source: [&str; 5], destination: [&str; 6], bytes: [&str; 2], speed: &str) -> RsyncStats {
+        let [source_total, source_files, source_dirs, source_links, source_specials] = source.map(String::from);
+        let [destination_total, destination_files, destination_dirs, destination_links, destination_specials, destination_deleted] = destination.map(String::from);
+        let [bytes_source, bytes_transferred] = bytes.map(String::from);
+
+        RsyncStats {
+            source_total, source_files, source_dirs, source_links, source_specials,
+            destination_total, destination_files, destination_dirs, destination_links, destination_specials, destination_deleted,
+            bytes_source, bytes_transferred,
+            speed: speed.to_owned()
+        }
+    }
+
+    #[test]
+    fn transfer_transcript() {
+        let (signals, code, messages) = run_transcript("transfer.txt");
+
+        let mut expected = vec!["start"];
+        expected.extend(["message"; 6]);
+        expected.extend(["progress"; 3]);
+        expected.extend(["message", "progress", "exit"]);
+
+        assert_eq!(signals, expected);
+        assert_eq!(code, 0);
+        assert!(messages.errors.is_empty());
+        assert!(!messages.stalled);
+
+        let files: Vec<&str> = messages.messages.iter()
+            .filter(|message| !message.changes.is_empty())
+            .map(|message| message.msg.as_str())
+            .collect();
+
+        assert_eq!(files, ["docs/", "docs/report.pdf", "docs/notes.txt"]);
+
+        assert_eq!(
+            RsyncProcess::stats(&messages.stats),
+            Some(expected_stats(["3", "2", "1", "0", "0"], ["2", "2", "1", "0", "0", "0"], ["1,286,877", "1,286,877"], "2,472,234.00"))
+        );
+    }
+
+    #[test]
+    fn vanished_transcript() {
+        let (signals, code, messages) = run_transcript("vanished.txt");
+
+        assert_eq!(signals, ["start", "message", "message", "message", "message", "progress", "exit"]);
+        assert_eq!(code, 24);
+        assert_eq!(messages.errors.len(), 2);
+        assert!(messages.errors[0].starts_with("File has vanished"));
+
+        assert_eq!(
+            RsyncProcess::stats(&messages.stats),
+            Some(expected_stats(["2", "2", "0", "0", "0"], ["1", "1", "0", "0", "0", "0"], ["2,048", "2,048"], "4,472.00"))
+        );
+    }
+
+    // Stats lines of a fixture transcript (stdout lines from "Number of files:" on)
+    fn fixture_stats(fixture: &str) -> Vec<String> {
+        let path = format!("{}/tests/transcripts/stats/{fixture}", env!("CARGO_MANIFEST_DIR"));
+
+        fs::read_to_string(path)
+            .expect("Could not read stats fixture")
+            .lines()
+            .filter_map(|line| line.strip_prefix("out ").or_else(|| (line == "out").then_some("")))
+            .skip_while(|line| !line.starts_with("Number of files:"))
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn stats_fixtures() {
+        let fixtures = [
+            ("openrsync.txt", expected_stats(["3", "0", "0", "0", "0"], ["1", "1", "0", "0", "0", "0"], ["1286877", "1234567"], "2470132.00")),
+            ("rsync-3.0.txt", expected_stats(["3", "0", "0", "0", "0"], ["1", "1", "0", "0", "0", "0"], ["1286877", "1234567"], "2470132.00")),
+            ("rsync-3.1.txt", expected_stats(["3", "2", "1", "0", "0"], ["1", "1", "0", "0", "0", "0"], ["1,286,877", "1,234,567"], "2,470,132.00")),
+            ("rsync-3.2.txt", expected_stats(["4", "2", "1", "1", "0"], ["1", "1", "0", "0", "0", "1"], ["1.29M", "1.23M"], "2.48M"))
+        ];
+
+        for (fixture, expected) in fixtures {
+            assert_eq!(RsyncProcess::stats(&fixture_stats(fixture)), Some(expected), "{fixture}");
+        }
+    }
+
+    #[test]
+    fn stats_without_summary() {
+        assert_eq!(RsyncProcess::stats(&[]), None);
+        assert_eq!(RsyncProcess::stats(&["Total file size: 10 bytes".to_owned()]), None);
+    }
+
+    // Waits for the kernel to tear down reparented orphans
+    fn group_gone(pgid: NixPid) -> bool {
+        (0..20).any(|_| {
+            let alive = group_alive(pgid);
+
+            if alive {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+
+            !alive
+        })
+    }
+
+    #[test]
+    fn terminate_escalates_to_kill() {
+        let fake_rsync = FakeRsync::new("orphan.txt");
+
+        let process = RsyncProcess::default();
+        process.set_program(fake_rsync.program.clone());
+
+        let pgid: Rc<Cell<Option<NixPid>>> = Rc::default();
+        let terminated: Rc<Cell<Option<Instant>>> = Rc::default();
+        let alive_after_term: Rc<Cell<bool>> = Rc::default();
+
+        process.connect_closure("start", false, closure_local!(
+            #[strong] pgid,
+            #[strong] terminated,
+            #[strong] alive_after_term,
+            move |process: RsyncProcess| {
+                let pid = process.imp().pid.get();
+
+                pgid.set(pid);
+
+                glib::timeout_add_local_once(Duration::from_millis(500), clone!(
+                    #[weak] process,
+                    #[strong] terminated,
+                    #[strong] alive_after_term,
+                    move || {
+                        // SIGTERM only, the orphan ignores it
+                        let _ = process.terminate();
+
+                        terminated.set(Some(Instant::now()));
+
+                        glib::timeout_add_local_once(Duration::from_secs(2), move || {
+                            alive_after_term.set(pid.is_some_and(group_alive));
+                        });
+                    }
+                ));
+            }
+        ));
+
+        block_on(process.start(vec![], RsyncRetry::default(), RsyncWatchdog::default()))
+            .expect("Could not run fake rsync");
+
+        let pgid = pgid.get()
+            .expect("Fake rsync did not start");
+
+        let elapsed = terminated.get()
+            .expect("Fake rsync was not terminated")
+            .elapsed();
+
+        // Orphan survived SIGTERM until the grace period ended in SIGKILL
+        assert!(alive_after_term.get(), "Orphan did not survive SIGTERM");
+        assert!(elapsed >= Duration::from_secs(u64::from(KILL_GRACE_PERIOD)), "Exited before grace period ({elapsed:?})");
+        assert!(group_gone(pgid), "Processes in rsync group survived terminate");
+    }
+
+    #[test]
+    fn exit_kills_leftover_group() {
+        let (_, code, _) = run_transcript("orphan-exit.txt");
+
+        // Returning at all means the orphan holding stdout open was killed
+        assert_eq!(code, 0);
+    }
+
+    #[test]
+    fn unescape_name_octal() {
+        assert_eq!(RsyncProcess::unescape_name(b"a\\#012b"), b"a\nb");
+        assert_eq!(RsyncProcess::unescape_name(b"\\#101\\#102"), b"AB");
+        assert_eq!(RsyncProcess::unescape_name(b"caf\\#303\\#251"), "caf\u{e9}".as_bytes());
+        assert_eq!(RsyncProcess::unescape_name(b"\\#377"), [0xFF]);
+    }
+
+    #[test]
+    fn unescape_name_invalid() {
+        // Over 0o377
+        assert_eq!(RsyncProcess::unescape_name(b"\\#400"), b"\\#400");
+        assert_eq!(RsyncProcess::unescape_name(b"\\#777x"), b"\\#777x");
+
+        // Truncated
+        assert_eq!(RsyncProcess::unescape_name(b"a\\#01"), b"a\\#01");
+        assert_eq!(RsyncProcess::unescape_name(b"a\\#"), b"a\\#");
+        assert_eq!(RsyncProcess::unescape_name(b"a\\"), b"a\\");
+
+        // Not octal
+        assert_eq!(RsyncProcess::unescape_name(b"\\#08a"), b"\\#08a");
+        assert_eq!(RsyncProcess::unescape_name(b"\\x101"), b"\\x101");
+    }
+}
@@ -0,0 +1,501 @@
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fmt;
+use std::sync::LazyLock;
+
+use gtk::gio;
+use regex::Regex;
+
+//------------------------------------------------------------------------------
+// CONST Variables
+//------------------------------------------------------------------------------
+pub const MIN_VERSION: (u32, u32, u32) = (3, 0, 0);
+
+// Short options and their long names
+const SHORT_OPTIONS: [(char, &str); 19] = [
+    ('a', "archive"),
+    ('b', "backup"),
+    ('c', "checksum"),
+    ('d', "dirs"),
+    ('D', "devices"),
+    ('g', "group"),
+    ('h', "human-readable"),
+    ('H', "hard-links"),
+    ('I', "ignore-times"),
+    ('l', "links"),
+    ('n', "dry-run"),
+    ('o', "owner"),
+    ('p', "perms"),
+    ('r', "recursive"),
+    ('t', "times"),
+    ('u', "update"),
+    ('v', "verbose"),
+    ('x', "one-file-system"),
+    ('z', "compress")
+];
+
+//------------------------------------------------------------------------------
+// STRUCT: RsyncVersion
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Eq, PartialEq, Clone)]
+pub struct RsyncVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub protocol: u32,
+    pub openrsync: bool
+}
+
+impl RsyncVersion {
+    //---------------------------------------
+    // Parse function
+    //---------------------------------------
+    // Parses rsync --version output (rsync and openrsync)
+    pub fn parse(output: &str) -> Option<Self> {
+        static VERSION_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"rsync\s+version\s+v?(?P<major>\d+)\.(?P<minor>\d+)(?:\.(?P<patch>\d+))?")
+                .expect("Failed to compile Regex")
+        });
+
+        static PROTOCOL_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"protocol\s+version\s+(?P<protocol>\d+)")
+                .expect("Failed to compile Regex")
+        });
+
+        let number = |caps: &regex::Captures, name: &str| -> u32 {
+            caps.name(name)
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or_default()
+        };
+
+        let openrsync = output.trim_start().starts_with("openrsync");
+
+        let protocol = PROTOCOL_EXPR.captures(output)
+            .map(|caps| number(&caps, "protocol"));
+
+        match VERSION_EXPR.captures(output) {
+            Some(caps) => Some(Self {
+                major: number(&caps, "major"),
+                minor: number(&caps, "minor"),
+                patch: number(&caps, "patch"),
+                protocol: protocol.unwrap_or_default(),
+                openrsync
+            }),
+            // openrsync only reports a protocol version
+            None if openrsync => protocol.map(|protocol| Self {
+                protocol,
+                openrsync,
+                ..Default::default()
+            }),
+            None => None
+        }
+    }
+
+    //---------------------------------------
+    // Detect function
+    //---------------------------------------
+    // Runs program --version and parses its output
+    pub async fn detect(program: &str) -> Result<Self, String> {
+        let output = run_output(program, "--version").await?;
+
+        Self::parse(&output)
+            .ok_or_else(|| format!("Could not parse version of \"{program}\""))
+    }
+
+    //---------------------------------------
+    // At least function
+    //---------------------------------------
+    pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        (self.major, self.minor, self.patch) >= (major, minor, patch)
+    }
+}
+
+impl fmt::Display for RsyncVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.openrsync {
+            write!(f, "openrsync (protocol {})", self.protocol)
+        } else {
+            write!(f, "rsync {}.{}.{} (protocol {})", self.major, self.minor, self.patch, self.protocol)
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// STRUCT: RsyncCapabilities
+//------------------------------------------------------------------------------
+#[derive(Default, Debug, Clone)]
+pub struct RsyncCapabilities {
+    pub program: String,
+    pub version: RsyncVersion,
+    options: HashSet<String>,
+    short_options: HashSet<char>
+}
+
+impl RsyncCapabilities {
+    //---------------------------------------
+    // Probe function
+    //---------------------------------------
+    // Detects version and options listed by program --help
+    pub async fn probe(program: &str) -> Result<Self, String> {
+        let version = RsyncVersion::detect(program).await?;
+
+        // openrsync prints usage to stderr and exits with an error
+        let help = run_output(program, "--help").await
+            .unwrap_or_default();
+
+        Ok(Self::from_help(program, version, &help))
+    }
+
+    //---------------------------------------
+    // From help function
+    //---------------------------------------
+    // Parses long and short options (" -t, --times", "--times, -t" or "[-rt]")
+    fn from_help(program: &str, version: RsyncVersion, help: &str) -> Self {
+        static OPTION_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"--(?P<option>[a-z0-9][a-z0-9-]*)")
+                .expect("Failed to compile Regex")
+        });
+
+        static SHORT_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r"(?m)^\s*-(?P<short>[a-zA-Z0-9])\b|--[a-z0-9-]+(?:=\S+)?,\s+-(?P<alias>[a-zA-Z0-9])\b|\[-(?P<flags>[a-zA-Z0-9]+)[\] ]")
+                .expect("Failed to compile Regex")
+        });
+
+        let options = OPTION_EXPR.captures_iter(help)
+            .map(|caps| caps["option"].to_owned())
+            .collect();
+
+        let short_options = SHORT_EXPR.captures_iter(help)
+            .flat_map(|caps| {
+                caps.name("short").or_else(|| caps.name("alias")).or_else(|| caps.name("flags"))
+                    .map(|m| m.as_str().chars().collect::<Vec<char>>())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        Self {
+            program: program.to_owned(),
+            version,
+            options,
+            short_options
+        }
+    }
+
+    //---------------------------------------
+    // Too old function
+    //---------------------------------------
+    pub fn too_old(&self) -> bool {
+        let (major, minor, patch) = MIN_VERSION;
+
+        !self.version.openrsync && !self.version.at_least(major, minor, patch)
+    }
+
+    //---------------------------------------
+    // Supports function
+    //---------------------------------------
+    // Takes a command line argument (e.g. "--numeric-ids", "--info=name" or "-rt")
+    pub fn supports(&self, arg: &str) -> bool {
+        let Some(option) = arg.strip_prefix("--") else {
+            return arg.strip_prefix('-')
+                .is_none_or(|flags| flags.chars().all(|flag| self.supports_short(flag)));
+        };
+
+        let option = option.split_once('=')
+            .map_or(option, |(option, _)| option);
+
+        let version = &self.version;
+
+        match option {
+            // Not listed by --help
+            "no-i-r" => !version.openrsync && version.at_least(3, 0, 0),
+            "info" | "debug" => !version.openrsync && version.at_least(3, 1, 0),
+            "filter" => true,
+            // Assume supported if --help could not be parsed (or only lists the short option)
+            _ => self.options.is_empty() || self.options.contains(option)
+                || short_option(option).is_some_and(|flag| self.short_options.contains(&flag))
+        }
+    }
+
+    //---------------------------------------
+    // Supports short function
+    //---------------------------------------
+    // Falls back to the long option name if --help lists no short options
+    fn supports_short(&self, flag: char) -> bool {
+        if !self.short_options.is_empty() {
+            return self.short_options.contains(&flag);
+        }
+
+        long_option(flag)
+            .is_none_or(|option| self.supports(&format!("--{option}")))
+    }
+
+    //---------------------------------------
+    // Unsupported reason function
+    //---------------------------------------
+    pub fn unsupported_reason(&self, arg: &str) -> Option<String> {
+        (!self.supports(arg)).then(|| {
+            let option = arg.split_once('=').map_or(arg, |(option, _)| option);
+
+            // Add long name of single short option (e.g. "-H (--hard-links)")
+            let long = option.strip_prefix('-')
+                .and_then(|flag| flag.parse::<char>().ok())
+                .and_then(long_option);
+
+            match long {
+                Some(long) => format!("{option} (--{long}) is not supported by {}", self.version),
+                None => format!("{option} is not supported by {}", self.version)
+            }
+        })
+    }
+
+    //---------------------------------------
+    // Filter args function
+    //---------------------------------------
+    // Splits arguments into supported and dropped ones (not supported by the installed rsync),
+    // fails with the reasons if a required argument is not supported
+    pub fn filter_args(&self, args: Vec<String>) -> Result<(Vec<String>, Vec<String>), Vec<String>> {
+        let (supported, dropped): (Vec<String>, Vec<String>) = args.into_iter()
+            .partition(|arg| self.supports(arg));
+
+        let required: Vec<String> = dropped.iter()
+            .filter(|arg| required_arg(arg))
+            .filter_map(|arg| self.unsupported_reason(arg))
+            .collect();
+
+        if required.is_empty() {
+            Ok((supported, dropped))
+        } else {
+            Err(required)
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// FUNCTION: Long option
+//------------------------------------------------------------------------------
+fn long_option(flag: char) -> Option<&'static str> {
+    SHORT_OPTIONS.iter()
+        .find(|(short, _)| *short == flag)
+        .map(|(_, option)| *option)
+}
+
+//------------------------------------------------------------------------------
+// FUNCTION: Short option
+//------------------------------------------------------------------------------
+fn short_option(option: &str) -> Option<char> {
+    SHORT_OPTIONS.iter()
+        .find(|(_, long)| *long == option)
+        .map(|(short, _)| *short)
+}
+
+//------------------------------------------------------------------------------
+// FUNCTION: Required arg
+//------------------------------------------------------------------------------
+// Arguments that protect or limit changes to the destination (never dropped)
+fn required_arg(arg: &str) -> bool {
+    let Some(option) = arg.strip_prefix("--") else {
+        return arg.strip_prefix('-')
+            .is_some_and(|flags| flags.chars().any(|flag| matches!(flag, 'n' | 'b' | 'x' | 'f')));
+    };
+
+    let option = option.split_once('=')
+        .map_or(option, |(option, _)| option);
+
+    matches!(option, "dry-run" | "del" | "filter" | "one-file-system" | "max-delete")
+        || ["delete", "exclude", "include", "backup"].iter().any(|prefix| option.starts_with(prefix))
+}
+
+//------------------------------------------------------------------------------
+// FUNCTION: Run output
+//------------------------------------------------------------------------------
+// Runs program with a single argument, returns stdout and stderr
+async fn run_output(program: &str, arg: &str) -> Result<String, String> {
+    let launcher = gio::SubprocessLauncher::new(gio::SubprocessFlags::STDOUT_PIPE | gio::SubprocessFlags::STDERR_MERGE);
+
+    launcher.setenv("LC_ALL", "C", true);
+
+    let process = launcher.spawnv(&[OsStr::new(program), OsStr::new(arg)])
+        .map_err(|error| error.to_string())?;
+
+    let (stdout, _) = process.communicate_utf8_future(None).await
+        .map_err(|error| error.to_string())?;
+
+    Ok(stdout.map(|s| s.to_string()).unwrap_or_default())
+}
+
+//------------------------------------------------------------------------------
+// TESTS
+//------------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(major: u32, minor: u32, patch: u32, protocol: u32, openrsync: bool) -> RsyncVersion {
+        RsyncVersion { major, minor, patch, protocol, openrsync }
+    }
+
+    #[test]
+    fn parse_versions() {
+        let versions = [
+            ("rsync  version 3.2.7  protocol version 31", Some(version(3, 2, 7, 31, false))),
+            ("rsync  version 3.2.7  protocol version 31\nCopyright (C) 1996-2022 by Andrew Tridgell", Some(version(3, 2, 7, 31, false))),
+            ("rsync version v3.1.3", Some(version(3, 1, 3, 0, false))),
+            ("rsync version 3.0", Some(version(3, 0, 0, 0, false))),
+            ("openrsync: protocol version 29", Some(version(0, 0, 0, 29, true))),
+            ("openrsync", None),
+            ("garbage", None),
+            ("", None)
+        ];
+
+        for (output, expected) in versions {
+            assert_eq!(RsyncVersion::parse(output), expected, "{output:?}");
+        }
+    }
+
+    #[test]
+    fn at_least() {
+        let version = version(3, 1, 3, 31, false);
+
+        assert!(version.at_least(3, 0, 0));
+        assert!(version.at_least(3, 1, 3));
+        assert!(!version.at_least(3, 2, 0));
+    }
+
+    const RSYNC_HELP: &str = "rsync  version 3.2.7  protocol version 31
+Options
+--verbose, -v            increase verbosity
+ -r, --recursive             recurse into directories
+ -t, --times                 preserve modification times
+ -H, --hard-links            preserve hard links
+ -D                          same as --devices --specials
+     --numeric-ids           don't map uid/gid values by user/group name
+     --delete                delete extraneous files from dest dirs
+     --out-format=FORMAT     output updates using the specified FORMAT";
+
+    const OPENRSYNC_HELP: &str = "usage: openrsync [-0cDdgklnoprtVvx] [-e program] [--address=sourceaddr]
+\t[--contimeout=seconds] [--del] [--delete] [--exclude] [--numeric-ids]
+\t[--timeout=seconds] source ... directory";
+
+    fn from_help(help: &str, version: RsyncVersion) -> RsyncCapabilities {
+        RsyncCapabilities::from_help("rsync", version, help)
+    }
+
+    fn to_args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|&arg| arg.to_owned()).collect()
+    }
+
+    #[test]
+    fn supports_rsync() {
+        let capabilities = from_help(RSYNC_HELP, version(3, 2, 7, 31, false));
+
+        for arg in ["-r", "-t", "-H", "-D", "-v", "-rt", "--numeric-ids", "--out-format=%n", "--info=name", "--no-i-r", "--filter=- *.o", "/home/user"] {
+            assert!(capabilities.supports(arg), "{arg}");
+        }
+
+        for arg in ["-x", "-u", "-rx", "--one-file-system", "--contimeout=10"] {
+            assert!(!capabilities.supports(arg), "{arg}");
+        }
+
+        assert_eq!(capabilities.unsupported_reason("-x").as_deref(), Some("-x (--one-file-system) is not supported by rsync 3.2.7 (protocol 31)"));
+        assert_eq!(capabilities.unsupported_reason("--contimeout=10").as_deref(), Some("--contimeout is not supported by rsync 3.2.7 (protocol 31)"));
+        assert_eq!(capabilities.unsupported_reason("-t"), None);
+    }
+
+    #[test]
+    fn supports_openrsync() {
+        let capabilities = from_help(OPENRSYNC_HELP, version(0, 0, 0, 29, true));
+
+        for arg in ["-t", "-p", "-o", "-g", "-l", "-D", "-x", "-r", "-d", "--numeric-ids", "--delete", "--timeout=10"] {
+            assert!(capabilities.supports(arg), "{arg}");
+        }
+
+        for arg in ["-H", "-u", "-b", "-I", "--partial", "--info=name", "--debug=filter", "--no-i-r"] {
+            assert!(!capabilities.supports(arg), "{arg}");
+        }
+    }
+
+    #[test]
+    fn supports_fake_rsync_help() {
+        let help = "--recursive, -r          recurse into directories
+--times, -t              preserve modification times
+--filter=RULE, -f        add a file-filtering RULE
+--one-file-system, -x    don't cross filesystem boundaries";
+
+        let capabilities = from_help(help, version(3, 2, 7, 31, false));
+
+        assert!(capabilities.supports("-r"));
+        assert!(capabilities.supports("-x"));
+        assert!(!capabilities.supports("-p"));
+    }
+
+    #[test]
+    fn supports_long_fallback() {
+        // No short options listed, fall back to long names
+        let capabilities = from_help("--times --perms --recursive", version(3, 2, 7, 31, false));
+
+        assert!(capabilities.supports("-t"));
+        assert!(capabilities.supports("-rp"));
+        assert!(!capabilities.supports("-H"));
+        assert!(capabilities.supports("-e"));
+
+        // Unparsable help, assume supported
+        let capabilities = from_help("", version(3, 2, 7, 31, false));
+
+        assert!(capabilities.supports("-H"));
+        assert!(capabilities.supports("--anything"));
+    }
+
+    #[test]
+    fn filter_args() {
+        let capabilities = from_help(OPENRSYNC_HELP, version(0, 0, 0, 29, true));
+
+        let (args, dropped) = capabilities.filter_args(to_args(&["-t", "-H", "--numeric-ids", "--partial", "--no-i-r", "-r", "--filter=- *.o", "--dry-run"]))
+            .expect("Required arguments dropped");
+
+        assert_eq!(args, ["-t", "--numeric-ids", "-r", "--filter=- *.o", "--dry-run"]);
+        assert_eq!(dropped, ["-H", "--partial", "--no-i-r"]);
+
+        let capabilities = from_help(RSYNC_HELP, version(3, 2, 7, 31, false));
+
+        let (args, dropped) = capabilities.filter_args(to_args(&["-r", "--no-i-r", "-t", "--info=name"]))
+            .expect("Required arguments dropped");
+
+        assert_eq!(args, ["-r", "--no-i-r", "-t", "--info=name"]);
+        assert!(dropped.is_empty());
+    }
+
+    #[test]
+    fn filter_args_keeps_required() {
+        // RSYNC_HELP lists neither --dry-run/-n, -x nor --delete-after
+        let capabilities = from_help(RSYNC_HELP, version(3, 2, 7, 31, false));
+
+        for arg in ["--dry-run", "-n", "-x", "--one-file-system", "--delete-after", "--exclude=*.o", "--backup-dir=old"] {
+            assert!(!capabilities.supports(arg), "{arg}");
+            assert!(capabilities.filter_args(to_args(&["-t", arg])).is_err(), "{arg}");
+        }
+
+        // A dry run never loses its flag
+        for (help, version) in [
+            (RSYNC_HELP, version(3, 2, 7, 31, false)),
+            (OPENRSYNC_HELP, version(0, 0, 0, 29, true)),
+            ("--times", version(3, 2, 7, 31, false)),
+            ("", version(3, 2, 7, 31, false))
+        ] {
+            if let Ok((args, dropped)) = from_help(help, version).filter_args(to_args(&["-t", "-H", "--dry-run"])) {
+                assert!(args.iter().any(|arg| arg == "--dry-run"), "{help:?}");
+                assert!(!dropped.iter().any(|arg| arg == "--dry-run"), "{help:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn dry_run_short_option() {
+        // openrsync only lists -n
+        let capabilities = from_help(OPENRSYNC_HELP, version(0, 0, 0, 29, true));
+
+        assert!(capabilities.supports("--dry-run"));
+        assert!(capabilities.supports("-n"));
+        assert_eq!(long_option('n'), Some("dry-run"));
+        assert_eq!(short_option("dry-run"), Some('n'));
+    }
+}
@@ -18,6 +18,43 @@ pub mod convert {
 
         if n1 > n2 { s1 } else { s2 }.to_owned()
     }
+
+    // Parses human-readable sizes (e.g. "12.34MB/s") in units of 1000
+    pub fn size_to_num(s: &str) -> f64 {
+        let s = s.trim_end_matches("/s")
+            .trim_end_matches('B')
+            .replace(THOUSANDS_SEP, "");
+
+        let (num, factor) = match s.chars().last() {
+            Some('K' | 'k') => (&s[..s.len() - 1], 1e3),
+            Some('M') => (&s[..s.len() - 1], 1e6),
+            Some('G') => (&s[..s.len() - 1], 1e9),
+            Some('T') => (&s[..s.len() - 1], 1e12),
+            Some('P') => (&s[..s.len() - 1], 1e15),
+            _ => (s.as_str(), 1.0)
+        };
+
+        num.parse::<f64>().unwrap_or_default() * factor
+    }
+
+    // Formats sizes in human-readable units of 1000 (e.g. "12.34M")
+    pub fn num_to_size(n: f64) -> String {
+        const UNITS: [&str; 6] = ["", "K", "M", "G", "T", "P"];
+
+        let mut n = n;
+        let mut unit = 0;
+
+        while n >= 1000.0 && unit < UNITS.len() - 1 {
+            n /= 1000.0;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            format!("{n:.0}")
+        } else {
+            format!("{n:.2}{}", UNITS[unit])
+        }
+    }
 }
 
 //------------------------------------------------------------------------------
@@ -1,4 +1,4 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 
 use adw::subclass::prelude::*;
 use adw::prelude::*;
@@ -8,10 +8,12 @@ use glib::{ clone, VariantTy};
 use crate::{
     Application,
     profile_object::ProfileObject,
+    filter_set_object::FilterSetObject,
     options_page::OptionsPage,
     advanced_page::AdvancedPage,
     rsync_page::RsyncPage,
-    rsync_process::ITEMIZE_TAG
+    rsync_process::{ITEMIZE_TAG, OUT_FORMAT},
+    rsync_version::{RsyncCapabilities, MIN_VERSION}
 };
 
 //------------------------------------------------------------------------------
@@ -30,6 +32,15 @@ mod imp {
         pub(super) status_stack: TemplateChild<gtk::Stack>,
         #[template_child]
         pub(super) status_new_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub(super) status_import_button: TemplateChild<gtk::Button>,
+
+        #[template_child]
+        pub(super) rsync_status_page: TemplateChild<adw::StatusPage>,
+        #[template_child]
+        pub(super) rsync_retry_button: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub(super) rsync_profiles_button: TemplateChild<gtk::Button>,
 
         #[template_child]
         pub(super) navigation_view: TemplateChild<adw::NavigationView>,
@@ -41,6 +52,7 @@ mod imp {
         pub(super) rsync_page: TemplateChild<RsyncPage>,
 
         pub(super) close_request: Cell<bool>,
+        pub(super) capabilities: RefCell<Option<RsyncCapabilities>>,
     }
 
     //---------------------------------------
@@ -54,6 +66,7 @@ mod imp {
 
         fn class_init(klass: &mut Self::Class) {
             ProfileObject::ensure_type();
+            FilterSetObject::ensure_type();
 
             klass.bind_template();
 
@@ -117,6 +130,11 @@ mod imp {
 
             let _ = self.options_page.save_config();
 
+            // Keep window open until config conflict is resolved
+            if self.options_page.conflict_pending() {
+                return glib::Propagation::Stop;
+            }
+
             glib::Propagation::Proceed
         }
     }
@@ -138,33 +156,95 @@ mod imp {
                         .and_then(|param| param.get::<bool>())
                         .expect("Could not get bool from variant");
 
-                    // Show rsync page
-                    imp.navigation_view.push_by_tag("rsync");
-
                     // Get profile
                     let profile = imp.options_page.profile_dropdown().selected_item()
                         .and_downcast::<ProfileObject>()
                         .expect("Could not downcast to 'ProfileObject'");
 
-                    // Get args
+                    // Refuse to start with filters rsync cannot parse or missing filter sets
+                    let invalid_filters = profile.invalid_filters();
+
+                    if !invalid_filters.is_empty() {
+                        let dialog = adw::AlertDialog::builder()
+                            .heading("Invalid Filter Rules")
+                            .body(format!("The profile contains filter rules that are not valid rsync filters or refer to missing filter sets. Edit or remove them before starting the transfer.\n\n{}",
+                                invalid_filters.join("\n")))
+                            .default_response("close")
+                            .build();
+
+                        dialog.add_responses(&[("close", "_Close")]);
+
+                        dialog.present(Some(window));
+
+                        return;
+                    }
+
+                    // Check rsync capabilities
+                    let Some(capabilities) = window.probe_rsync(&profile.rsync_program(), true).await else {
+                        return;
+                    };
+
+                    // Get args (fall back to per-file progress if --info is not supported)
+                    let info_args = if capabilities.supports("--info") {
+                        vec![
+                            "--info=backup,copy,del,flist2,misc,name,progress2,skip2,symsafe,stats2",
+                            "--debug=filter"
+                        ]
+                    } else {
+                        vec!["--progress", "--stats"]
+                    };
+
                     let args = profile.options(false)
                         .into_iter()
                         .chain(
                             [
-                                "--human-readable",
-                                &format!("--out-format={ITEMIZE_TAG}%i %n%L"),
-                                "--info=backup,copy,del,flist2,misc,name,progress2,skip2,symsafe,stats2",
-                                "--debug=filter"
+                                &format!("--out-format={ITEMIZE_TAG}{OUT_FORMAT}")
                             ]
                             .into_iter()
+                            .chain(info_args)
                             .chain(dry_run.then_some("--dry-run"))
                             .map(ToOwned::to_owned)
                         )
+                        .collect::<Vec<_>>();
+
+                    // Refuse to start if rsync does not support a required option
+                    let (args, dropped) = match capabilities.filter_args(args) {
+                        Ok(args) => args,
+                        Err(reasons) => {
+                            let dialog = adw::AlertDialog::builder()
+                                .heading("Unsupported Rsync Options")
+                                .body(format!("The installed rsync does not support options that protect the destination. The transfer was not started.\n\n{}",
+                                    reasons.join("\n")))
+                                .default_response("close")
+                                .build();
+
+                            dialog.add_responses(&[("close", "_Close")]);
+
+                            dialog.present(Some(window));
+
+                            return;
+                        }
+                    };
+
+                    // Show rsync page
+                    imp.navigation_view.push_by_tag("rsync");
+
+                    let args = args.into_iter()
                         .chain([profile.source(), profile.destination()])
                         .collect::<Vec<_>>();
 
+                    // Warn about options not supported by rsync
+                    imp.rsync_page.set_dropped_options(&dropped);
+
                     // Start rsync
-                    let _ = imp.rsync_page.rsync_process().start(args).await;
+                    imp.rsync_page.rsync_process().set_program(profile.rsync_program());
+
+                    if imp.rsync_page.rsync_process().start(args, profile.retry(), profile.watchdog()).await.is_err() {
+                        // Probe again (shows status page if rsync was removed)
+                        imp.capabilities.replace(None);
+
+                        window.probe_rsync(&profile.rsync_program(), true).await;
+                    }
                 }
             );
 
@@ -186,9 +266,25 @@ mod imp {
                     .and_downcast::<ProfileObject>()
                     .expect("Could not downcast to 'ProfileObject'");
 
-                // Init command line dialog
-                label.set_label(&format!("rsync {} \"{}\" \"{}\"",
-                    profile.options(true).join(" "),
+                // Init command line dialog (without options not supported by rsync)
+                let capabilities = imp.capabilities.borrow()
+                    .as_ref()
+                    .filter(|capabilities| capabilities.program == profile.rsync_program())
+                    .cloned();
+
+                let options = match &capabilities {
+                    Some(capabilities) => {
+                        dialog.set_body(&capabilities.version.to_string());
+
+                        capabilities.filter_args(profile.options(true))
+                            .map_or_else(|_| profile.options(true), |(options, _)| options)
+                    }
+                    None => profile.options(true)
+                };
+
+                label.set_label(&format!("{} {} \"{}\" \"{}\"",
+                    profile.rsync_program(),
+                    options.join(" "),
                     profile.source(),
                     profile.destination()
                 ));
@@ -254,6 +350,53 @@ impl AppWindow {
             }
         ));
 
+        // Status import button clicked signal
+        imp.status_import_button.connect_clicked(clone!(
+            #[weak] imp,
+            move |_| {
+                imp.options_page.activate_action("profile.import", None)
+                    .expect("Could not activate action 'profile.import'");
+            }
+        ));
+
+        // Rsync retry button clicked signal
+        imp.rsync_retry_button.connect_clicked(clone!(
+            #[weak(rename_to = window)] self,
+            move |_| {
+                window.imp().capabilities.replace(None);
+
+                glib::spawn_future_local(clone!(
+                    #[weak] window,
+                    async move {
+                        if window.probe_rsync(&window.selected_program(), true).await.is_some() {
+                            window.show_main();
+                        }
+                    }
+                ));
+            }
+        ));
+
+        // Rsync profiles button clicked signal
+        imp.rsync_profiles_button.connect_clicked(clone!(
+            #[weak(rename_to = window)] self,
+            move |_| {
+                window.show_main();
+            }
+        ));
+
+        // Profile dropdown selected item property notify signal
+        imp.options_page.profile_dropdown().connect_selected_item_notify(clone!(
+            #[weak(rename_to = window)] self,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak] window,
+                    async move {
+                        window.probe_rsync(&window.selected_program(), false).await;
+                    }
+                ));
+            }
+        ));
+
         // Navigation view get next page signal
         imp.navigation_view.connect_get_next_page(clone!(
             #[weak] imp,
@@ -305,6 +448,12 @@ impl AppWindow {
             .sync_create()
             .build();
 
+        // Set advanced page profile model (for template selection)
+        imp.advanced_page.set_profile_model(Some(&imp.options_page.profile_model()));
+
+        // Set advanced page filter set model
+        imp.advanced_page.set_filter_set_model(Some(&imp.options_page.filter_set_model()));
+
         // Bind selected profile to advanced page
         profile_dropdown.bind_property("selected-item", &imp.advanced_page.get(), "profile")
             .sync_create()
@@ -317,5 +466,97 @@ impl AppWindow {
 
         // Load profiles from config file
         let _ = imp.options_page.load_config();
+
+        // Probe rsync capabilities
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)] self,
+            async move {
+                window.probe_rsync(&window.selected_program(), true).await;
+            }
+        ));
+    }
+
+    //---------------------------------------
+    // Selected program helper function
+    //---------------------------------------
+    fn selected_program(&self) -> String {
+        self.imp().options_page.profile_dropdown().selected_item()
+            .and_downcast::<ProfileObject>()
+            .map_or_else(ProfileObject::default_program, |profile| profile.rsync_program())
+    }
+
+    //---------------------------------------
+    // Show main function
+    //---------------------------------------
+    fn show_main(&self) {
+        let imp = self.imp();
+
+        if imp.options_page.profile_model().n_items() == 0 {
+            imp.status_stack.set_visible_child_name("status");
+        } else {
+            imp.status_stack.set_visible_child_name("main");
+        }
+    }
+
+    //---------------------------------------
+    // Probe rsync async function
+    //---------------------------------------
+    // Returns capabilities of program (cached), optionally shows status page on failure
+    async fn probe_rsync(&self, program: &str, show_status: bool) -> Option<RsyncCapabilities> {
+        let imp = self.imp();
+
+        let cached = imp.capabilities.borrow()
+            .as_ref()
+            .filter(|capabilities| capabilities.program == program)
+            .cloned();
+
+        if cached.is_some() {
+            return cached;
+        }
+
+        let (title, description) = match RsyncCapabilities::probe(program).await {
+            Ok(capabilities) if !capabilities.too_old() => {
+                imp.advanced_page.set_capabilities(Some(&capabilities));
+
+                imp.capabilities.replace(Some(capabilities.clone()));
+
+                return Some(capabilities);
+            }
+            Ok(capabilities) => {
+                let (major, minor, patch) = MIN_VERSION;
+
+                ("Rsync Too Old", format!("Syncer requires rsync {major}.{minor}.{patch} or later, but \"{program}\" is {}", capabilities.version))
+            }
+            Err(error) => {
+                ("Rsync Not Found", format!("Could not run \"{program}\": {error}\n\nInstall rsync, or set the rsync executable in the advanced settings of the profile"))
+            }
+        };
+
+        if show_status {
+            imp.rsync_status_page.set_title(title);
+            imp.rsync_status_page.set_description(Some(glib::markup_escape_text(&description).as_str()));
+
+            imp.navigation_view.pop_to_tag("options");
+
+            imp.status_stack.set_visible_child_name("rsync");
+        }
+
+        None
+    }
+
+    //---------------------------------------
+    // Import profiles function
+    //---------------------------------------
+    pub fn import_profiles(&self, files: &[gio::File]) {
+        let files = files.to_vec();
+
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to = window)] self,
+            async move {
+                for file in files {
+                    window.imp().options_page.import_profiles(&file).await;
+                }
+            }
+        ));
     }
 }
@@ -0,0 +1,131 @@
+#!/usr/bin/env bash
+
+#==========================================================================================
+# FAKE RSYNC
+#==========================================================================================
+# Replays a recorded rsync transcript instead of transferring files. Point Syncer to it
+# with SYNCER_RSYNC=tests/fake-rsync.bash (or the profile's rsync executable setting).
+#
+# FAKE_RSYNC_TRANSCRIPT   transcript file to replay (default: transcripts/transfer.txt)
+# FAKE_RSYNC_SPEED        divides all sleep durations (default: 1)
+# FAKE_RSYNC_VERSION      first line printed for --version
+#                         (default: rsync  version 3.2.7  protocol version 31)
+# FAKE_RSYNC_HELP         file printed for --help (default: options used by Syncer)
+#
+# Transcript lines:
+#   out <text>     write line to stdout
+#   raw <text>     write text to stdout without newline, expanding escapes (e.g. \r)
+#   err <text>     write line to stderr
+#   sleep <secs>   pause (fractions allowed)
+#   spawn <cmd>    run command in background, ignoring SIGTERM (orphan test)
+#   exit <code>    exit with code
+#   # ...          comment
+
+#==========================================================================================
+# SCRIPT VARIABLES
+#==========================================================================================
+
+SCRIPTFOLDER=$(dirname $(realpath $0))
+
+TRANSCRIPT="${FAKE_RSYNC_TRANSCRIPT:-$SCRIPTFOLDER/transcripts/transfer.txt}"
+SPEED="${FAKE_RSYNC_SPEED:-1}"
+VERSION="${FAKE_RSYNC_VERSION:-rsync  version 3.2.7  protocol version 31}"
+HELP="${FAKE_RSYNC_HELP:-}"
+
+#==========================================================================================
+# PRINT VERSION
+#==========================================================================================
+print_version()
+{
+	printf '%s\n' "$VERSION"
+	echo "Copyright (C) 1996-2022 by Andrew Tridgell, Wayne Davison, and others."
+	echo "Web site: https://rsync.samba.org/"
+
+	exit 0
+}
+
+#==========================================================================================
+# PRINT HELP
+#==========================================================================================
+print_help()
+{
+	if [[ -n "$HELP" ]]; then
+		cat "$HELP"
+		exit 0
+	fi
+
+	printf '%s\n' "$VERSION"
+	echo ""
+	echo "Options"
+	echo "--recursive, -r          recurse into directories"
+	echo "--dirs, -d               transfer directories without recursing"
+	echo "--times, -t              preserve modification times"
+	echo "--perms, -p              preserve permissions"
+	echo "--owner, -o              preserve owner (super-user only)"
+	echo "--group, -g              preserve group"
+	echo "--links, -l              copy symlinks as symlinks"
+	echo "--hard-links, -H         preserve hard links"
+	echo "-D                       same as --devices --specials"
+	echo "--update, -u             skip files that are newer on the receiver"
+	echo "--backup, -b             make backups"
+	echo "--numeric-ids            don't map uid/gid values by user/group name"
+	echo "--one-file-system, -x    don't cross filesystem boundaries"
+	echo "--existing               skip creating new files on receiver"
+	echo "--ignore-existing        skip updating files that exist on receiver"
+	echo "--delete                 delete extraneous files from dest dirs"
+	echo "--partial                keep partially transferred files"
+	echo "--ignore-times, -I       don't skip files that match size and time"
+	echo "--size-only              skip files that match in size"
+	echo "--checksum, -c           skip based on checksum, not mod-time & size"
+	echo "--timeout=SECONDS        set I/O timeout in seconds"
+	echo "--contimeout=SECONDS     set daemon connection timeout in seconds"
+	echo "--dry-run, -n            perform a trial run with no changes made"
+	echo "--list-only              list the files instead of copying them"
+	echo "--filter=RULE, -f        add a file-filtering RULE"
+	echo "--out-format=FORMAT      output updates using the specified FORMAT"
+	echo "--human-readable, -h     output numbers in a human-readable format"
+	echo "--info=FLAGS             fine-grained informational verbosity"
+	echo "--debug=FLAGS            fine-grained debug verbosity"
+	echo "--progress               show progress during transfer"
+
+	exit 0
+}
+
+#==========================================================================================
+# REPLAY TRANSCRIPT
+#==========================================================================================
+replay_transcript()
+{
+	if [[ ! -f "$TRANSCRIPT" ]]; then
+		echo "fake-rsync: transcript not found: $TRANSCRIPT" >&2
+		exit 1
+	fi
+
+	local cmd text
+
+	while IFS= read -r line || [[ -n "$line" ]]; do
+		cmd="${line%% *}"
+		text="${line#"$cmd"}"
+		text="${text# }"
+
+		case "$cmd" in
+			out)   printf '%s\n' "$text" ;;
+			raw)   printf '%b' "$text" ;;
+			err)   printf '%s\n' "$text" >&2 ;;
+			sleep) sleep $(awk "BEGIN { print $text / $SPEED }") ;;
+			spawn) ( trap '' TERM; exec $text ) & ;;
+			exit)  exit "$text" ;;
+			""|\#*) ;;
+			*)     echo "fake-rsync: unknown transcript command: $cmd" >&2 ;;
+		esac
+	done < "$TRANSCRIPT"
+
+	exit 0
+}
+
+for arg in "$@"; do
+	[[ "$arg" == "--version" || "$arg" == "-V" ]] && print_version
+	[[ "$arg" == "--help" ]] && print_help
+done
+
+replay_transcript